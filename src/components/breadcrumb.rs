@@ -0,0 +1,331 @@
+use crate::{Component, Event, Spannable};
+use crossterm::event::{KeyCode, MouseButton, MouseEventKind};
+use std::path::{Path, PathBuf};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Paragraph, Widget};
+
+const SEPARATOR: &str = " › ";
+const ELLIPSIS: &str = "…";
+
+/// A path drawn as a row of its ancestors, each of which can be jumped to.
+///
+/// While focused, Left and Right highlight a segment and Enter jumps to it. Clicking
+/// a segment jumps to it at any time, if the terminal has mouse capture enabled
+#[derive(Debug, Clone, Default)]
+pub struct Breadcrumb {
+    /// the path and each of its ancestors, starting from the root
+    segments: Vec<PathBuf>,
+    /// the segment highlighted while focused
+    selected: usize,
+    pub focused: bool,
+    /// the columns each segment was last drawn at, and the row
+    hitboxes: Vec<(u16, u16, usize)>,
+    row: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreadcrumbResponse {
+    /// an ancestor was chosen
+    Jump(PathBuf),
+    Handled,
+    /// focus should leave the breadcrumb
+    Exit,
+    None,
+}
+
+/// What's drawn in place of each segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Segment(usize),
+    /// stands for segments left out to fit
+    Ellipsis,
+}
+
+impl Breadcrumb {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let mut breadcrumb = Self::default();
+        breadcrumb.set_path(path);
+        breadcrumb
+    }
+
+    /// Changes the path, highlighting its last segment unless it's unchanged
+    pub fn set_path<P: AsRef<Path>>(&mut self, path: P) {
+        if self.segments.last().map(PathBuf::as_path) == Some(path.as_ref()) {
+            return;
+        }
+        let mut segments = path
+            .as_ref()
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .collect::<Vec<_>>();
+        segments.reverse();
+        self.selected = segments.len().saturating_sub(1);
+        self.segments = segments;
+        // the old segments can't be clicked until the new ones are drawn
+        self.hitboxes.clear();
+        self.row = None;
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.segments.last().map(PathBuf::as_path)
+    }
+
+    /// The segment highlighted while focused
+    pub fn selected(&self) -> Option<&Path> {
+        self.segments.get(self.selected).map(PathBuf::as_path)
+    }
+
+    fn label(&self, index: usize) -> String {
+        let segment = &self.segments[index];
+        match segment.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            // the root, or a path such as ".."
+            None => segment.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Chooses the segments to draw within a width, leaving out middle segments from
+    /// the root onwards. The root, the last segment and the highlighted one are kept
+    fn layout(&self, width: usize) -> Vec<Item> {
+        let widths = (0..self.segments.len())
+            .map(|i| Span::raw(self.label(i)).width())
+            .collect::<Vec<_>>();
+        let last = self.segments.len().saturating_sub(1);
+        let mut shown = vec![true; self.segments.len()];
+        let items = |shown: &[bool]| {
+            let mut items = Vec::new();
+            for (i, show) in shown.iter().enumerate() {
+                if *show {
+                    items.push(Item::Segment(i));
+                } else if items.last() != Some(&Item::Ellipsis) {
+                    items.push(Item::Ellipsis);
+                }
+            }
+            items
+        };
+        let items_width = |items: &[Item]| {
+            let separators = items.len().saturating_sub(1) * Span::raw(SEPARATOR).width();
+            let labels = items
+                .iter()
+                .map(|item| match item {
+                    Item::Segment(i) => widths[*i],
+                    Item::Ellipsis => Span::raw(ELLIPSIS).width(),
+                })
+                .sum::<usize>();
+            separators + labels
+        };
+        for i in 1..last {
+            if items_width(&items(&shown)) <= width {
+                break;
+            }
+            if i != self.selected {
+                shown[i] = false;
+            }
+        }
+        items(&shown)
+    }
+
+    fn spans<'b>(&self, width: usize) -> Spans<'b> {
+        let last = self.segments.len().saturating_sub(1);
+        let mut spans = Vec::new();
+        for (k, item) in self.layout(width).into_iter().enumerate() {
+            if k > 0 {
+                spans.push(Span::styled(
+                    SEPARATOR,
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            let (text, mut style) = match item {
+                Item::Segment(i) if i == last => (
+                    self.label(i),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Item::Segment(i) => (self.label(i), Style::default().fg(Color::Cyan)),
+                Item::Ellipsis => (ELLIPSIS.to_string(), Style::default().fg(Color::DarkGray)),
+            };
+            if self.focused && item == Item::Segment(self.selected) {
+                style = style.bg(Color::Blue);
+            }
+            spans.push(Span::styled(text, style));
+        }
+        Spans::from(spans)
+    }
+}
+
+impl Component for Breadcrumb {
+    type Response = BreadcrumbResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        match event {
+            Event::Key(key_event) if self.focused => match key_event.code {
+                KeyCode::Left => {
+                    self.selected = self.selected.saturating_sub(1);
+                    BreadcrumbResponse::Handled
+                }
+                KeyCode::Right => {
+                    self.selected = (self.selected + 1).min(self.segments.len().saturating_sub(1));
+                    BreadcrumbResponse::Handled
+                }
+                KeyCode::Home => {
+                    self.selected = 0;
+                    BreadcrumbResponse::Handled
+                }
+                KeyCode::End => {
+                    self.selected = self.segments.len().saturating_sub(1);
+                    BreadcrumbResponse::Handled
+                }
+                KeyCode::Enter => match self.segments.get(self.selected) {
+                    Some(segment) => BreadcrumbResponse::Jump(segment.clone()),
+                    None => BreadcrumbResponse::Handled,
+                },
+                KeyCode::Esc | KeyCode::Down => BreadcrumbResponse::Exit,
+                _ => BreadcrumbResponse::None,
+            },
+            Event::Mouse(mouse_event)
+                if mouse_event.kind == MouseEventKind::Down(MouseButton::Left)
+                    && self.row == Some(mouse_event.row) =>
+            {
+                let clicked = self
+                    .hitboxes
+                    .iter()
+                    .find(|(start, end, _)| (*start..*end).contains(&mouse_event.column))
+                    .and_then(|(_, _, index)| Some((*index, self.segments.get(*index)?.clone())));
+                match clicked {
+                    Some((index, segment)) => {
+                        self.selected = index;
+                        BreadcrumbResponse::Jump(segment)
+                    }
+                    None => BreadcrumbResponse::None,
+                }
+            }
+            _ => BreadcrumbResponse::None,
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
+        self.hitboxes.clear();
+        self.row = None;
+        if rect.height == 0 {
+            return;
+        }
+        let spans = self.spans(rect.width as usize);
+        // the hitboxes follow the same layout as the spans
+        let mut x = rect.x;
+        for item in self.layout(rect.width as usize) {
+            let width = match item {
+                Item::Segment(i) => {
+                    let width = Span::raw(self.label(i)).width() as u16;
+                    self.hitboxes.push((x, x.saturating_add(width), i));
+                    width
+                }
+                Item::Ellipsis => Span::raw(ELLIPSIS).width() as u16,
+            };
+            x = x
+                .saturating_add(width)
+                .saturating_add(Span::raw(SEPARATOR).width() as u16);
+        }
+        self.row = Some(rect.y);
+        Widget::render(Paragraph::new(spans), rect, buf);
+    }
+}
+
+impl Spannable for Breadcrumb {
+    /// The spans of the whole path, without leaving out any segments
+    fn get_spans<'b>(&self) -> Spans<'b> {
+        self.spans(usize::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers, MouseEvent};
+
+    fn click(column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    #[test]
+    fn leaves_out_middle_segments_to_fit() {
+        let mut breadcrumb = Breadcrumb::new("/a/bb/ccc/dddd");
+        let all = (0..5).map(Item::Segment).collect::<Vec<_>>();
+        assert_eq!(breadcrumb.layout(23), all);
+        assert_eq!(
+            breadcrumb.layout(20),
+            [
+                Item::Segment(0),
+                Item::Ellipsis,
+                Item::Segment(3),
+                Item::Segment(4)
+            ]
+        );
+        // the root and the last segment are kept even when they don't fit
+        assert_eq!(
+            breadcrumb.layout(1),
+            [Item::Segment(0), Item::Ellipsis, Item::Segment(4)]
+        );
+        breadcrumb.focused = true;
+        breadcrumb.handle_event(Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)));
+        breadcrumb.handle_event(Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)));
+        assert_eq!(
+            breadcrumb.layout(1),
+            [
+                Item::Segment(0),
+                Item::Ellipsis,
+                Item::Segment(2),
+                Item::Ellipsis,
+                Item::Segment(4)
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_clicks_to_drawn_segments() {
+        let mut breadcrumb = Breadcrumb::new("/a/b/c");
+        let rect = Rect::new(0, 2, 40, 1);
+        breadcrumb.draw(rect, &mut Buffer::empty(rect));
+        // "/ › a › b › c"
+        assert_eq!(
+            breadcrumb.handle_event(click(8, 2)),
+            BreadcrumbResponse::Jump(PathBuf::from("/a/b"))
+        );
+        assert_eq!(breadcrumb.selected(), Some(Path::new("/a/b")));
+        assert_eq!(
+            breadcrumb.handle_event(click(2, 2)),
+            BreadcrumbResponse::None
+        );
+        assert_eq!(
+            breadcrumb.handle_event(click(8, 3)),
+            BreadcrumbResponse::None
+        );
+    }
+
+    #[test]
+    fn ignores_clicks_on_segments_of_an_old_path() {
+        let mut breadcrumb = Breadcrumb::new("/a/b/c");
+        let rect = Rect::new(0, 0, 40, 1);
+        breadcrumb.draw(rect, &mut Buffer::empty(rect));
+        breadcrumb.set_path("/a");
+        assert_eq!(
+            breadcrumb.handle_event(click(12, 0)),
+            BreadcrumbResponse::None
+        );
+        breadcrumb.draw(rect, &mut Buffer::empty(rect));
+        assert_eq!(
+            breadcrumb.handle_event(click(4, 0)),
+            BreadcrumbResponse::Jump(PathBuf::from("/a"))
+        );
+    }
+}
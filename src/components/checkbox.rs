@@ -78,7 +78,7 @@ impl Component for Checkbox {
 }
 
 impl Spannable for Checkbox {
-    fn get_spans<'a, 'b>(&'a self) -> Spans<'b> {
+    fn get_spans<'b>(&self) -> Spans<'b> {
         let mut spans = Spans::default();
         spans.0.push(Span::raw(String::from("> ")));
         if self.value {
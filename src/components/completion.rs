@@ -0,0 +1,39 @@
+/// Supplies completion candidates for the value of an [`Input`](crate::components::Input)
+pub trait Completer {
+    /// Returns candidates for the value, each being a full replacement for it
+    fn complete(&self, value: &str) -> Vec<String>;
+}
+
+impl<F: Fn(&str) -> Vec<String>> Completer for F {
+    fn complete(&self, value: &str) -> Vec<String> {
+        self(value)
+    }
+}
+
+/// Completes from a fixed list of words, matching by case-insensitive prefix
+#[derive(Debug, Clone, Default)]
+pub struct WordCompleter {
+    words: Vec<String>,
+}
+
+impl WordCompleter {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(words: I) -> Self {
+        Self {
+            words: words.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Completer for WordCompleter {
+    fn complete(&self, value: &str) -> Vec<String> {
+        if value.is_empty() {
+            return Vec::new();
+        }
+        let value = value.to_lowercase();
+        self.words
+            .iter()
+            .filter(|word| word.to_lowercase().starts_with(&value))
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,555 @@
+use super::{EntryKind, EntryMeta, FileSystem, ReadSeek};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How many archives have their index kept, the least recently used being dropped
+const CACHE_CAPACITY: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// A member of an archive, with its position for reading it back
+#[derive(Debug, Clone)]
+struct Member {
+    meta: EntryMeta,
+    position: Option<usize>,
+    /// where the contents start in an uncompressed tar, so they can be read directly
+    offset: Option<u64>,
+}
+
+/// The index of an archive's members. The contents are read again when a member is read
+#[derive(Debug)]
+struct LoadedArchive {
+    format: ArchiveFormat,
+    modified: Option<SystemTime>,
+    /// members by their path within the archive, with the root as an empty path
+    members: BTreeMap<PathBuf, Member>,
+}
+
+/// Wraps a file system, browsing `.zip`, `.tar`, `.tar.gz` and `.tgz` files as directories.
+///
+/// Members have archive-qualified paths, such as `release.zip/bin/tool`, which
+/// [`ArchiveFileSystem::split_path`] separates. Archives are read-only
+#[derive(Debug)]
+pub struct ArchiveFileSystem {
+    inner: Box<dyn FileSystem>,
+    /// the most recently used first
+    archives: Mutex<VecDeque<(PathBuf, Arc<LoadedArchive>)>>,
+}
+
+impl ArchiveFileSystem {
+    pub fn new<F: FileSystem + 'static>(inner: F) -> Self {
+        Self {
+            inner: Box::new(inner),
+            archives: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Splits an archive-qualified path into the archive and the member's path within it.
+    ///
+    /// Returns `None` if the path isn't inside an archive
+    pub fn split_path(path: &Path) -> Option<(PathBuf, PathBuf)> {
+        let mut ancestors = path.ancestors().skip(1).collect::<Vec<_>>();
+        ancestors.reverse();
+        ancestors
+            .into_iter()
+            .find(|ancestor| ArchiveFormat::of(ancestor).is_some())
+            .map(|archive| {
+                let member = path.strip_prefix(archive).unwrap_or(path);
+                (archive.to_path_buf(), member.to_path_buf())
+            })
+    }
+
+    /// Finds the archive containing a path, which may be the archive itself
+    fn resolve(&self, path: &Path) -> Option<(PathBuf, PathBuf)> {
+        let mut ancestors = path.ancestors().collect::<Vec<_>>();
+        ancestors.reverse();
+        ancestors
+            .into_iter()
+            .find(|ancestor| {
+                ArchiveFormat::of(ancestor).is_some()
+                    && self
+                        .inner
+                        .metadata(ancestor)
+                        .is_ok_and(|meta| meta.is_file())
+            })
+            .map(|archive| {
+                let member = path.strip_prefix(archive).unwrap_or(path);
+                (archive.to_path_buf(), member.to_path_buf())
+            })
+    }
+
+    /// Reads and indexes an archive, reusing the index until it's modified
+    fn load(&self, path: &Path) -> io::Result<Arc<LoadedArchive>> {
+        let meta = self.inner.metadata(path)?;
+        {
+            let mut archives = self.archives.lock().unwrap();
+            if let Some(i) = archives.iter().position(|(cached, _)| cached == path) {
+                let (cached, archive) = archives.remove(i).unwrap();
+                if archive.modified == meta.modified {
+                    archives.push_front((cached, archive.clone()));
+                    return Ok(archive);
+                }
+            }
+        }
+        let format = ArchiveFormat::of(path)
+            .ok_or_else(|| invalid(format!("{} is not an archive", path.display())))?;
+        let reader = self.open_archive(path)?;
+        let members = match format {
+            ArchiveFormat::Zip => index_zip(reader)?,
+            // the headers are read, seeking past the contents
+            ArchiveFormat::Tar => index_tar(tar::Archive::new(reader).entries_with_seek()?, true)?,
+            ArchiveFormat::TarGz => {
+                let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+                index_tar(tar.entries()?, false)?
+            }
+        };
+        let archive = Arc::new(LoadedArchive {
+            format,
+            modified: meta.modified,
+            members,
+        });
+        let mut archives = self.archives.lock().unwrap();
+        archives.push_front((path.to_path_buf(), archive.clone()));
+        archives.truncate(CACHE_CAPACITY);
+        Ok(archive)
+    }
+
+    /// Opens an archive to read parts of it, or reads it whole if the file system
+    /// can't open files
+    fn open_archive(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        match self.inner.open(path) {
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+                Ok(Box::new(Cursor::new(self.inner.read(path, u64::MAX)?)))
+            }
+            opened => opened,
+        }
+    }
+
+    fn member(&self, archive: &Path, member: &Path) -> io::Result<(Arc<LoadedArchive>, Member)> {
+        let loaded = self.load(archive)?;
+        let found = loaded.members.get(member).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} not found", archive.join(member).display()),
+            )
+        })?;
+        Ok((loaded, found))
+    }
+
+    /// Fails for paths inside archives, which can't be changed
+    fn writable(&self, path: &Path) -> io::Result<()> {
+        match self.resolve(path) {
+            Some((_, member)) if !member.as_os_str().is_empty() => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "archives are read-only",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FileSystem for ArchiveFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<(PathBuf, EntryMeta)>> {
+        match self.resolve(path) {
+            Some((archive, member)) => {
+                let (loaded, found) = self.member(&archive, &member)?;
+                if !found.meta.is_dir() {
+                    return Err(invalid(format!("{} is not a directory", path.display())));
+                }
+                Ok(loaded
+                    .members
+                    .iter()
+                    .filter(|(name, _)| name.parent() == Some(member.as_path()))
+                    .map(|(name, found)| (archive.join(name), found.meta.clone()))
+                    .collect())
+            }
+            None => Ok(self
+                .inner
+                .read_dir(path)?
+                .into_iter()
+                .map(|(path, mut meta)| {
+                    if meta.is_file() && ArchiveFormat::of(&path).is_some() {
+                        meta.kind = EntryKind::Directory;
+                    }
+                    (path, meta)
+                })
+                .collect()),
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<EntryMeta> {
+        match self.resolve(path) {
+            Some((archive, member)) if member.as_os_str().is_empty() => {
+                let mut meta = self.inner.metadata(&archive)?;
+                meta.kind = EntryKind::Directory;
+                Ok(meta)
+            }
+            Some((archive, member)) => Ok(self.member(&archive, &member)?.1.meta),
+            None => self.inner.metadata(path),
+        }
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<EntryMeta> {
+        match self.resolve(path) {
+            Some(_) => self.metadata(path),
+            None => self.inner.symlink_metadata(path),
+        }
+    }
+
+    fn read(&self, path: &Path, limit: u64) -> io::Result<Vec<u8>> {
+        let (archive, member) = match self.resolve(path) {
+            Some(found) => found,
+            None => return self.inner.read(path, limit),
+        };
+        let (loaded, found) = self.member(&archive, &member)?;
+        let position = match found.position {
+            Some(position) if found.meta.is_file() => position,
+            _ => return Err(invalid(format!("{} is not a file", path.display()))),
+        };
+        let mut reader = self.open_archive(&archive)?;
+        let mut bytes = Vec::new();
+        match (loaded.format, found.offset) {
+            (ArchiveFormat::Zip, _) => {
+                let mut zip = zip::ZipArchive::new(reader).map_err(zip_error)?;
+                let file = zip.by_index(position).map_err(zip_error)?;
+                file.take(limit).read_to_end(&mut bytes)?;
+            }
+            (ArchiveFormat::Tar, Some(offset)) => {
+                reader.seek(SeekFrom::Start(offset))?;
+                reader
+                    .take(limit.min(found.meta.len))
+                    .read_to_end(&mut bytes)?;
+            }
+            (format, _) => {
+                // compressed, so read through from the start
+                let reader: Box<dyn Read> = if format == ArchiveFormat::TarGz {
+                    Box::new(flate2::read::GzDecoder::new(reader))
+                } else {
+                    Box::new(reader)
+                };
+                let mut tar = tar::Archive::new(reader);
+                let entry = tar
+                    .entries()?
+                    .nth(position)
+                    .ok_or_else(|| invalid(String::from("archive member is missing")))??;
+                entry.take(limit).read_to_end(&mut bytes)?;
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        match self.resolve(path) {
+            Some(_) => Ok(Box::new(Cursor::new(self.read(path, u64::MAX)?))),
+            None => self.inner.open(path),
+        }
+    }
+
+    fn is_archive(&self, path: &Path) -> bool {
+        self.resolve(path)
+            .is_some_and(|(_, member)| member.as_os_str().is_empty())
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.writable(path)?;
+        self.inner.create_dir(path)
+    }
+
+    fn create_file(&self, path: &Path) -> io::Result<()> {
+        self.writable(path)?;
+        self.inner.create_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.writable(from)?;
+        self.writable(to)?;
+        self.inner.rename(from, to)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        self.writable(path)?;
+        self.inner.remove(path)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.writable(from)?;
+        self.writable(to)?;
+        self.inner.copy(from, to)
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn zip_error(e: zip::result::ZipError) -> io::Error {
+    invalid(format!("{}", e))
+}
+
+/// Keeps only the normal components of a member's path, dropping `.` and roots
+fn member_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Adds a member, along with any parent directories the archive doesn't list
+fn insert_member(members: &mut BTreeMap<PathBuf, Member>, path: PathBuf, member: Member) {
+    for ancestor in path.ancestors().skip(1) {
+        members
+            .entry(ancestor.to_path_buf())
+            .or_insert_with(|| Member {
+                meta: EntryMeta::directory(),
+                position: None,
+                offset: None,
+            });
+    }
+    members.insert(path, member);
+}
+
+fn root() -> BTreeMap<PathBuf, Member> {
+    let mut members = BTreeMap::new();
+    members.insert(
+        PathBuf::new(),
+        Member {
+            meta: EntryMeta::directory(),
+            position: None,
+            offset: None,
+        },
+    );
+    members
+}
+
+/// Indexes a zip from its central directory, without reading the contents
+fn index_zip<R: Read + Seek>(reader: R) -> io::Result<BTreeMap<PathBuf, Member>> {
+    let mut zip = zip::ZipArchive::new(reader).map_err(zip_error)?;
+    let mut members = root();
+    for position in 0..zip.len() {
+        let file = zip.by_index(position).map_err(zip_error)?;
+        let path = member_path(Path::new(file.name()));
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let mut meta = if file.is_dir() {
+            EntryMeta::directory()
+        } else {
+            EntryMeta::file(file.size())
+        };
+        let time = file.last_modified();
+        meta.modified = system_time(
+            time.year() as i64,
+            time.month() as i64,
+            time.day() as i64,
+            time.hour() as u64 * 3600 + time.minute() as u64 * 60 + time.second() as u64,
+        );
+        meta.mode = file.unix_mode();
+        insert_member(
+            &mut members,
+            path,
+            Member {
+                meta,
+                position: Some(position),
+                offset: None,
+            },
+        );
+    }
+    Ok(members)
+}
+
+/// Indexes a tar from its headers, noting where contents start if `seekable`
+fn index_tar<R: Read>(
+    entries: tar::Entries<'_, R>,
+    seekable: bool,
+) -> io::Result<BTreeMap<PathBuf, Member>> {
+    let mut members = root();
+    for (position, entry) in entries.enumerate() {
+        let entry = entry?;
+        let header = entry.header();
+        let path = member_path(&entry.path()?);
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let entry_type = header.entry_type();
+        let mut meta = if entry_type.is_dir() {
+            EntryMeta::directory()
+        } else {
+            EntryMeta::file(header.size().unwrap_or(0))
+        };
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            meta.kind = EntryKind::Symlink;
+        } else if !entry_type.is_dir() && !entry_type.is_file() {
+            meta.kind = EntryKind::Other;
+        }
+        meta.modified = header
+            .mtime()
+            .ok()
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        meta.mode = header.mode().ok();
+        // sparse files aren't stored contiguously
+        let contiguous = seekable && entry_type == tar::EntryType::Regular;
+        insert_member(
+            &mut members,
+            path,
+            Member {
+                meta,
+                position: Some(position),
+                offset: contiguous.then(|| entry.raw_file_position()),
+            },
+        );
+    }
+    Ok(members)
+}
+
+/// Converts a civil date in UTC, and the seconds into that day, to a time
+fn system_time(year: i64, month: i64, day: i64, seconds: u64) -> Option<SystemTime> {
+    // days since the epoch from a civil date (Howard Hinnant's algorithm)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    let days = u64::try_from(days).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86400 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::preview::Preview;
+    use super::*;
+    use crate::components::MemoryFileSystem;
+
+    fn tar(contents: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "dir/file.txt", contents)
+            .unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    fn files(format: ArchiveFormat) -> Vec<u8> {
+        let files: [(&str, &[u8]); 2] = [("a.txt", b"first"), ("b/c.txt", b"second")];
+        if format == ArchiveFormat::Zip {
+            let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+            for (name, contents) in files {
+                zip.start_file(name, zip::write::FileOptions::default())
+                    .unwrap();
+                io::Write::write_all(&mut zip, contents).unwrap();
+            }
+            return zip.finish().unwrap().into_inner();
+        }
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, name, contents).unwrap();
+        }
+        let bytes = builder.into_inner().unwrap();
+        if format == ArchiveFormat::Tar {
+            return bytes;
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        io::Write::write_all(&mut gz, &bytes).unwrap();
+        gz.finish().unwrap()
+    }
+
+    #[test]
+    fn browses_and_reads_members() {
+        let fs = ArchiveFileSystem::new(MemoryFileSystem::new().file("/a.tar", tar(b"hello")));
+        assert!(fs.metadata(Path::new("/a.tar")).unwrap().is_dir());
+        let listed = fs.read_dir(Path::new("/a.tar/dir")).unwrap();
+        assert_eq!(listed[0].0, Path::new("/a.tar/dir/file.txt"));
+        assert_eq!(
+            fs.read(Path::new("/a.tar/dir/file.txt"), 4).unwrap(),
+            b"hell"
+        );
+        assert!(fs.create_file(Path::new("/a.tar/new.txt")).is_err());
+    }
+
+    #[test]
+    fn reads_members_of_each_format() {
+        for (name, format) in [
+            ("/a.zip", ArchiveFormat::Zip),
+            ("/a.tar", ArchiveFormat::Tar),
+            ("/a.tar.gz", ArchiveFormat::TarGz),
+        ] {
+            let fs = ArchiveFileSystem::new(MemoryFileSystem::new().file(name, files(format)));
+            let archive = Path::new(name);
+            assert_eq!(fs.read(&archive.join("b/c.txt"), 100).unwrap(), b"second");
+            assert_eq!(fs.read(&archive.join("a.txt"), 3).unwrap(), b"fir");
+            let mut opened = String::new();
+            fs.open(&archive.join("b/c.txt"))
+                .unwrap()
+                .read_to_string(&mut opened)
+                .unwrap();
+            assert_eq!(opened, "second");
+        }
+    }
+
+    #[test]
+    fn previews_archives_without_reading_them() {
+        let fs = ArchiveFileSystem::new(
+            MemoryFileSystem::new().file("/a.zip", files(ArchiveFormat::Zip)),
+        );
+        assert!(fs.is_archive(Path::new("/a.zip")));
+        assert!(!fs.is_archive(Path::new("/a.zip/b")));
+        match Preview::load(&fs, Path::new("/a.zip")) {
+            Preview::Metadata(lines) => assert_eq!(lines[0], "Archive"),
+            preview => panic!("unexpected preview {:?}", preview),
+        }
+        assert!(fs.archives.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn keeps_a_bounded_number_of_archives() {
+        let mut inner = MemoryFileSystem::new();
+        for i in 0..CACHE_CAPACITY + 2 {
+            inner = inner.file(format!("/{}.tar", i), tar(b""));
+        }
+        let fs = ArchiveFileSystem::new(inner);
+        // the archive itself is described without being read
+        fs.metadata(Path::new("/0.tar")).unwrap();
+        assert!(fs.archives.lock().unwrap().is_empty());
+        for i in 0..CACHE_CAPACITY + 2 {
+            fs.read_dir(Path::new(&format!("/{}.tar", i))).unwrap();
+        }
+        let archives = fs.archives.lock().unwrap();
+        assert_eq!(archives.len(), CACHE_CAPACITY);
+        assert_eq!(
+            archives[0].0,
+            Path::new(&format!("/{}.tar", CACHE_CAPACITY + 1))
+        );
+    }
+}
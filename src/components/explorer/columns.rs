@@ -0,0 +1,283 @@
+use super::{EntryInfo, EntryMeta};
+use std::cmp::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A column of metadata shown by the [`Explorer`](crate::components::Explorer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerColumn {
+    Name,
+    Size,
+    Modified,
+    Permissions,
+    Type,
+}
+
+impl ExplorerColumn {
+    pub(super) fn title(&self) -> &'static str {
+        match self {
+            ExplorerColumn::Name => "Name",
+            ExplorerColumn::Size => "Size",
+            ExplorerColumn::Modified => "Modified",
+            ExplorerColumn::Permissions => "Permissions",
+            ExplorerColumn::Type => "Type",
+        }
+    }
+
+    /// The fixed width of the column, with the name taking the remaining space
+    pub(super) fn width(&self) -> Option<u16> {
+        match self {
+            ExplorerColumn::Name => None,
+            ExplorerColumn::Size => Some(10),
+            ExplorerColumn::Modified => Some(16),
+            ExplorerColumn::Permissions => Some(11),
+            ExplorerColumn::Type => Some(8),
+        }
+    }
+
+    /// The text shown in this column for an entry, other than the name
+    pub(super) fn value(&self, entry: &EntryInfo) -> String {
+        let meta = &entry.meta;
+        match self {
+            ExplorerColumn::Name => String::new(),
+            ExplorerColumn::Size if meta.resolves_to_dir() => String::new(),
+            ExplorerColumn::Size => format_size(meta.len),
+            ExplorerColumn::Modified => meta.modified.map(format_time).unwrap_or_default(),
+            ExplorerColumn::Permissions => format_permissions(meta),
+            ExplorerColumn::Type => type_name(entry),
+        }
+    }
+}
+
+/// What the entries of an [`Explorer`](crate::components::Explorer) are ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Size,
+    Modified,
+    Type,
+}
+
+impl SortKey {
+    pub(super) fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::Size,
+            SortKey::Size => SortKey::Modified,
+            SortKey::Modified => SortKey::Type,
+            SortKey::Type => SortKey::Name,
+        }
+    }
+
+    pub(super) fn column(self) -> ExplorerColumn {
+        match self {
+            SortKey::Name => ExplorerColumn::Name,
+            SortKey::Size => ExplorerColumn::Size,
+            SortKey::Modified => ExplorerColumn::Modified,
+            SortKey::Type => ExplorerColumn::Type,
+        }
+    }
+}
+
+/// How the entries of an [`Explorer`](crate::components::Explorer) are ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub key: SortKey,
+    pub ascending: bool,
+    /// lists directories before files, regardless of the key
+    pub directories_first: bool,
+    /// compares names ignoring case, with runs of digits compared by value
+    pub natural: bool,
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        Self {
+            key: SortKey::Name,
+            ascending: true,
+            directories_first: true,
+            natural: true,
+        }
+    }
+}
+
+impl SortOrder {
+    pub(super) fn sort(&self, entries: &mut [EntryInfo]) {
+        entries.sort_by(|a, b| {
+            let dirs = if self.directories_first {
+                b.meta.resolves_to_dir().cmp(&a.meta.resolves_to_dir())
+            } else {
+                Ordering::Equal
+            };
+            let names = || {
+                let (a, b) = (name_of(a), name_of(b));
+                if self.natural {
+                    natural_cmp(&a, &b)
+                } else {
+                    a.cmp(&b)
+                }
+            };
+            let by_key = match self.key {
+                SortKey::Name => Ordering::Equal,
+                SortKey::Size => a.meta.len.cmp(&b.meta.len),
+                SortKey::Modified => a.meta.modified.cmp(&b.meta.modified),
+                SortKey::Type => type_name(a).cmp(&type_name(b)),
+            };
+            let by_key = by_key.then_with(names);
+            dirs.then(if self.ascending {
+                by_key
+            } else {
+                by_key.reverse()
+            })
+        });
+    }
+}
+
+pub(super) fn name_of(entry: &EntryInfo) -> String {
+    entry
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+pub(super) fn type_name(entry: &EntryInfo) -> String {
+    if entry.meta.error.is_some() {
+        String::from("?")
+    } else if entry.meta.is_broken_link() {
+        String::from("broken")
+    } else if entry.meta.is_symlink() {
+        String::from("link")
+    } else if entry.meta.is_dir() {
+        String::from("dir")
+    } else {
+        entry
+            .path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| String::from("file"))
+    }
+}
+
+/// Compares strings ignoring case, treating runs of digits as numbers
+pub(super) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_digits = take_digits(&mut a_chars);
+                let y_digits = take_digits(&mut b_chars);
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_digits<I: Iterator<Item = char>>(chars: &mut std::iter::Peekable<I>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+pub(super) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Formats a time as `YYYY-MM-DD HH:MM`, in UTC
+pub(super) fn format_time(time: SystemTime) -> String {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let days = secs.div_euclid(86400);
+    let rem = secs.rem_euclid(86400);
+    // civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+/// Formats unix permission bits as `rwxr-xr-x`, or whether the entry is read-only
+pub(super) fn format_permissions(meta: &EntryMeta) -> String {
+    let mode = match meta.mode {
+        Some(mode) => mode,
+        None if meta.readonly => return String::from("read-only"),
+        None => return String::from("read-write"),
+    };
+    let mut text = String::with_capacity(9);
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        text.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn compares_numbers_by_value() {
+        let mut names = vec!["file10", "file2", "File1", "file02", "file", "a"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["a", "file", "File1", "file02", "file2", "file10"]);
+        assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+        assert_eq!(natural_cmp("abc", "ABC"), "abc".cmp("ABC"));
+    }
+
+    #[test]
+    fn formats_times_as_utc_dates() {
+        assert_eq!(format_time(UNIX_EPOCH), "1970-01-01 00:00");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(951_827_640);
+        assert_eq!(format_time(leap_day), "2000-02-29 12:34");
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(60);
+        assert_eq!(format_time(before_epoch), "1969-12-31 23:59");
+    }
+}
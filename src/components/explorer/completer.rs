@@ -0,0 +1,103 @@
+use super::places::home;
+use super::{natural_cmp, FileSystem, StdFileSystem};
+use crate::components::Completer;
+use std::path::{is_separator, Component, Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::Arc;
+
+/// Completes paths typed relative to a base directory, or absolute or starting with `~`.
+///
+/// Each candidate completes the last component, with directories ending in a separator.
+/// Values that don't look like a path, such as plain names, have no candidates
+#[derive(Debug, Clone)]
+pub struct PathCompleter {
+    fs: Arc<dyn FileSystem>,
+    base: PathBuf,
+    directories_only: bool,
+}
+
+impl PathCompleter {
+    pub fn new<P: AsRef<Path>>(base: P) -> Self {
+        Self::with_file_system(base, Arc::new(StdFileSystem))
+    }
+
+    pub fn with_file_system<P: AsRef<Path>>(base: P, fs: Arc<dyn FileSystem>) -> Self {
+        Self {
+            fs,
+            base: base.as_ref().to_path_buf(),
+            directories_only: false,
+        }
+    }
+
+    /// Whether only directories are offered
+    pub fn directories_only(mut self, directories_only: bool) -> Self {
+        self.directories_only = directories_only;
+        self
+    }
+}
+
+impl Completer for PathCompleter {
+    fn complete(&self, value: &str) -> Vec<String> {
+        if !is_path(value) {
+            return Vec::new();
+        }
+        let (dir, partial) = match value.rfind(is_separator) {
+            // separators are all ascii
+            Some(i) => value.split_at(i + 1),
+            None if value == "~" || value == "." || value == ".." => {
+                return vec![format!("{}{}", value, MAIN_SEPARATOR)];
+            }
+            None => return Vec::new(),
+        };
+        let entries = match self.fs.read_dir(&resolve(&self.base, dir)) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        let mut candidates = entries
+            .into_iter()
+            .filter(|(_, meta)| !self.directories_only || meta.resolves_to_dir())
+            .filter_map(|(path, meta)| {
+                let name = path.file_name()?.to_string_lossy().into_owned();
+                // dotfiles are only offered once a dot is typed
+                if !name.starts_with(partial) || (name.starts_with('.') && partial.is_empty()) {
+                    return None;
+                }
+                let separator = if meta.resolves_to_dir() {
+                    MAIN_SEPARATOR.to_string()
+                } else {
+                    String::new()
+                };
+                Some(format!("{}{}{}", dir, name, separator))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| natural_cmp(a, b));
+        candidates
+    }
+}
+
+/// Whether a typed value should be treated as a path rather than a name
+pub(super) fn is_path(value: &str) -> bool {
+    value.starts_with('~') || value == "." || value == ".." || value.contains(is_separator)
+}
+
+/// Expands a leading `~` and resolves a typed path against the base directory,
+/// removing `.` and `..` components
+pub(super) fn resolve(base: &Path, value: &str) -> PathBuf {
+    let expanded = match value.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(is_separator) => match home() {
+            Some(home) => home.join(rest.trim_start_matches(is_separator)),
+            None => PathBuf::from(value),
+        },
+        _ => PathBuf::from(value),
+    };
+    let mut resolved = PathBuf::new();
+    for component in base.join(expanded).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            _ => resolved.push(component),
+        }
+    }
+    resolved
+}
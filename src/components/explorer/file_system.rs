@@ -0,0 +1,651 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// What kind of entry a path refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+    /// sockets, devices, and anything else
+    Other,
+}
+
+/// The metadata the [`Explorer`](crate::components::Explorer) shows and sorts by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryMeta {
+    pub kind: EntryKind,
+    /// the size in bytes
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+    /// unix permission bits, if the file system has them
+    pub mode: Option<u32>,
+    pub readonly: bool,
+    /// where a symlink points
+    pub link: Option<Link>,
+    /// why the entry's metadata couldn't be read, leaving the rest unknown
+    pub error: Option<String>,
+}
+
+/// The target of a symlink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub target: PathBuf,
+    /// what the target is, or `None` if the link is broken
+    pub target_kind: Option<EntryKind>,
+}
+
+impl EntryMeta {
+    pub fn file(len: u64) -> Self {
+        Self {
+            kind: EntryKind::File,
+            len,
+            modified: None,
+            mode: None,
+            readonly: false,
+            link: None,
+            error: None,
+        }
+    }
+
+    pub fn directory() -> Self {
+        Self {
+            kind: EntryKind::Directory,
+            ..Self::file(0)
+        }
+    }
+
+    /// An entry whose metadata couldn't be read
+    pub fn unreadable<E: ToString>(error: E) -> Self {
+        Self {
+            kind: EntryKind::Other,
+            error: Some(error.to_string()),
+            ..Self::file(0)
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Directory
+    }
+
+    /// Whether the entry is a directory, or a link to one
+    pub fn resolves_to_dir(&self) -> bool {
+        self.is_dir()
+            || self
+                .link
+                .as_ref()
+                .is_some_and(|link| link.target_kind == Some(EntryKind::Directory))
+    }
+
+    pub fn is_broken_link(&self) -> bool {
+        self.link
+            .as_ref()
+            .is_some_and(|link| link.target_kind.is_none())
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == EntryKind::File
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.kind == EntryKind::Symlink
+    }
+}
+
+impl From<fs::Metadata> for EntryMeta {
+    fn from(meta: fs::Metadata) -> Self {
+        let file_type = meta.file_type();
+        let kind = if file_type.is_symlink() {
+            EntryKind::Symlink
+        } else if file_type.is_dir() {
+            EntryKind::Directory
+        } else if file_type.is_file() {
+            EntryKind::File
+        } else {
+            EntryKind::Other
+        };
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(meta.permissions().mode())
+        };
+        #[cfg(not(unix))]
+        let mode = None;
+        Self {
+            kind,
+            len: meta.len(),
+            modified: meta.modified().ok(),
+            mode,
+            readonly: meta.permissions().readonly(),
+            link: None,
+            error: None,
+        }
+    }
+}
+
+/// A file opened with [`FileSystem::open`]
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+fn unsupported<T>() -> io::Result<T> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "not supported by this file system",
+    ))
+}
+
+/// The file system browsed by an [`Explorer`](crate::components::Explorer).
+///
+/// Only listing and metadata are required. Reading and changing files are
+/// unsupported unless implemented
+pub trait FileSystem: Debug + Send + Sync {
+    /// Lists the entries of a directory, without following links
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<(PathBuf, EntryMeta)>>;
+
+    /// Lists a directory one entry at a time, stopping early once `visit` returns false.
+    /// Used when loading in the background, so long listings appear as they're read
+    fn visit_dir(
+        &self,
+        path: &Path,
+        visit: &mut dyn FnMut(PathBuf, EntryMeta) -> bool,
+    ) -> io::Result<()> {
+        for (path, meta) in self.read_dir(path)? {
+            if !visit(path, meta) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// The metadata of a path, following links
+    fn metadata(&self, path: &Path) -> io::Result<EntryMeta>;
+
+    /// The metadata of a path itself, without following links. File systems without
+    /// links can rely on [`FileSystem::metadata`]
+    fn symlink_metadata(&self, path: &Path) -> io::Result<EntryMeta> {
+        self.metadata(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    fn parent(&self, path: &Path) -> Option<PathBuf> {
+        path.parent().map(Path::to_path_buf)
+    }
+
+    /// Reads up to `limit` bytes from the start of a file
+    fn read(&self, _path: &Path, _limit: u64) -> io::Result<Vec<u8>> {
+        unsupported()
+    }
+
+    /// Opens a file to read from any position, such as to read part of an archive
+    fn open(&self, _path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        unsupported()
+    }
+
+    /// Whether a path is an archive browsed as a directory. Listing one means reading
+    /// it, so previews leave it unlisted
+    fn is_archive(&self, _path: &Path) -> bool {
+        false
+    }
+
+    fn create_dir(&self, _path: &Path) -> io::Result<()> {
+        unsupported()
+    }
+
+    /// Creates an empty file, failing if it exists
+    fn create_file(&self, _path: &Path) -> io::Result<()> {
+        unsupported()
+    }
+
+    fn rename(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        unsupported()
+    }
+
+    /// Removes a file, or a directory with its contents
+    fn remove(&self, _path: &Path) -> io::Result<()> {
+        unsupported()
+    }
+
+    /// Copies a file, or a directory with its contents
+    fn copy(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        unsupported()
+    }
+}
+
+/// The local file system, through [`std::fs`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdFileSystem;
+
+impl FileSystem for StdFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<(PathBuf, EntryMeta)>> {
+        let mut entries = Vec::new();
+        self.visit_dir(path, &mut |path, meta| {
+            entries.push((path, meta));
+            true
+        })?;
+        Ok(entries)
+    }
+
+    fn visit_dir(
+        &self,
+        path: &Path,
+        visit: &mut dyn FnMut(PathBuf, EntryMeta) -> bool,
+    ) -> io::Result<()> {
+        // entries that can't be read at all have no path to show
+        for entry in fs::read_dir(path)?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let meta = match entry.metadata() {
+                Ok(meta) => {
+                    let mut meta = EntryMeta::from(meta);
+                    if meta.is_symlink() {
+                        meta.link = Some(Link {
+                            target: fs::read_link(&path).unwrap_or_default(),
+                            target_kind: fs::metadata(&path)
+                                .ok()
+                                .map(|target| EntryMeta::from(target).kind),
+                        });
+                    }
+                    meta
+                }
+                Err(e) => EntryMeta::unreadable(e),
+            };
+            if !visit(path, meta) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<EntryMeta> {
+        fs::metadata(path).map(EntryMeta::from)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<EntryMeta> {
+        fs::symlink_metadata(path).map(EntryMeta::from)
+    }
+
+    fn read(&self, path: &Path, limit: u64) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        fs::File::open(path)?.take(limit).read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        Ok(Box::new(fs::File::open(path)?))
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir(path)
+    }
+
+    fn create_file(&self, path: &Path) -> io::Result<()> {
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map(|_| ())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        if fs::symlink_metadata(path)?.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+    }
+
+    /// Links are copied as links, rather than followed
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        if fs::symlink_metadata(to).is_ok() {
+            return Err(already_exists(to));
+        }
+        let result = copy_tree(from, to);
+        if result.is_err() {
+            // don't leave half a copy behind
+            let _ = self.remove(to);
+        }
+        result
+    }
+}
+
+fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    let meta = fs::symlink_metadata(from)?;
+    if meta.file_type().is_symlink() {
+        copy_link(from, to)
+    } else if meta.is_dir() {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(windows)]
+fn copy_link(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::windows::fs::{symlink_dir, symlink_file};
+    let target = fs::read_link(from)?;
+    if fs::metadata(from).is_ok_and(|meta| meta.is_dir()) {
+        symlink_dir(target, to)
+    } else {
+        symlink_file(target, to)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn copy_link(_from: &Path, _to: &Path) -> io::Result<()> {
+    unsupported()
+}
+
+#[derive(Debug, Clone)]
+enum MemoryNode {
+    File(Vec<u8>),
+    Directory,
+}
+
+/// A file system held in memory, for virtual trees and deterministic tests.
+///
+/// Paths are absolute, and parent directories are created as needed
+#[derive(Debug)]
+pub struct MemoryFileSystem {
+    nodes: RwLock<BTreeMap<PathBuf, MemoryNode>>,
+}
+
+impl Default for MemoryFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryFileSystem {
+    /// Creates a file system containing only the root directory
+    pub fn new() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(PathBuf::from("/"), MemoryNode::Directory);
+        Self {
+            nodes: RwLock::new(nodes),
+        }
+    }
+
+    /// Adds a file, replacing any existing contents
+    pub fn file<P: AsRef<Path>, C: Into<Vec<u8>>>(self, path: P, contents: C) -> Self {
+        self.insert(path.as_ref(), MemoryNode::File(contents.into()));
+        self
+    }
+
+    pub fn directory<P: AsRef<Path>>(self, path: P) -> Self {
+        self.insert(path.as_ref(), MemoryNode::Directory);
+        self
+    }
+
+    fn insert(&self, path: &Path, node: MemoryNode) {
+        let mut nodes = self.nodes.write().unwrap();
+        for ancestor in path.ancestors().skip(1) {
+            nodes
+                .entry(ancestor.to_path_buf())
+                .or_insert(MemoryNode::Directory);
+        }
+        nodes.insert(path.to_path_buf(), node);
+    }
+
+    fn create(&self, path: &Path, node: MemoryNode) -> io::Result<()> {
+        let mut nodes = self.nodes.write().unwrap();
+        if nodes.contains_key(path) {
+            return Err(already_exists(path));
+        }
+        match path.parent().and_then(|parent| nodes.get(parent)) {
+            Some(MemoryNode::Directory) => {
+                nodes.insert(path.to_path_buf(), node);
+                Ok(())
+            }
+            _ => Err(not_found(path.parent().unwrap_or(path))),
+        }
+    }
+
+    fn meta(node: &MemoryNode) -> EntryMeta {
+        match node {
+            MemoryNode::File(contents) => EntryMeta::file(contents.len() as u64),
+            MemoryNode::Directory => EntryMeta::directory(),
+        }
+    }
+
+    /// The paths to move or copy from `from`, checking that they can go to `to`
+    fn transfer(
+        nodes: &BTreeMap<PathBuf, MemoryNode>,
+        from: &Path,
+        to: &Path,
+    ) -> io::Result<Vec<PathBuf>> {
+        if nodes.contains_key(to) {
+            return Err(already_exists(to));
+        }
+        let paths = Self::subtree(nodes, from);
+        if paths.is_empty() {
+            return Err(not_found(from));
+        }
+        if to.starts_with(from) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} can't go inside itself", from.display()),
+            ));
+        }
+        match to.parent().and_then(|parent| nodes.get(parent)) {
+            Some(MemoryNode::Directory) => Ok(paths),
+            _ => Err(not_found(to.parent().unwrap_or(to))),
+        }
+    }
+
+    /// Every path at or below `path`
+    fn subtree(nodes: &BTreeMap<PathBuf, MemoryNode>, path: &Path) -> Vec<PathBuf> {
+        nodes
+            .keys()
+            .filter(|key| key.starts_with(path))
+            .cloned()
+            .collect()
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} not found", path.display()),
+    )
+}
+
+fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", path.display()),
+    )
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<(PathBuf, EntryMeta)>> {
+        let nodes = self.nodes.read().unwrap();
+        match nodes.get(path) {
+            Some(MemoryNode::Directory) => Ok(nodes
+                .iter()
+                .filter(|(key, _)| key.parent() == Some(path))
+                .map(|(key, node)| (key.clone(), Self::meta(node)))
+                .collect()),
+            Some(MemoryNode::File(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", path.display()),
+            )),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<EntryMeta> {
+        let nodes = self.nodes.read().unwrap();
+        nodes
+            .get(path)
+            .map(Self::meta)
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn read(&self, path: &Path, limit: u64) -> io::Result<Vec<u8>> {
+        let nodes = self.nodes.read().unwrap();
+        match nodes.get(path) {
+            Some(MemoryNode::File(contents)) => {
+                Ok(contents[..contents.len().min(limit as usize)].to_vec())
+            }
+            Some(MemoryNode::Directory) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is a directory", path.display()),
+            )),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+        Ok(Box::new(Cursor::new(self.read(path, u64::MAX)?)))
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.create(path, MemoryNode::Directory)
+    }
+
+    fn create_file(&self, path: &Path) -> io::Result<()> {
+        self.create(path, MemoryNode::File(Vec::new()))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut nodes = self.nodes.write().unwrap();
+        let moved = Self::transfer(&nodes, from, to)?;
+        for path in moved {
+            let node = nodes.remove(&path).unwrap();
+            let relative = path.strip_prefix(from).unwrap();
+            nodes.insert(to.join(relative), node);
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let mut nodes = self.nodes.write().unwrap();
+        let removed = Self::subtree(&nodes, path);
+        if removed.is_empty() {
+            return Err(not_found(path));
+        }
+        for path in removed {
+            nodes.remove(&path);
+        }
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut nodes = self.nodes.write().unwrap();
+        let copied = Self::transfer(&nodes, from, to)?;
+        for path in copied {
+            let node = nodes[&path].clone();
+            let relative = path.strip_prefix(from).unwrap();
+            nodes.insert(to.join(relative), node);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    fn tree() -> MemoryFileSystem {
+        MemoryFileSystem::new()
+            .file("/a/one.txt", "1")
+            .file("/a/b/two.txt", "2")
+            .directory("/c")
+    }
+
+    fn paths(fs: &MemoryFileSystem) -> Vec<PathBuf> {
+        fs.nodes.read().unwrap().keys().cloned().collect()
+    }
+
+    #[test]
+    fn moves_and_copies_subtrees() {
+        let fs = tree();
+        fs.copy(Path::new("/a"), Path::new("/c/a")).unwrap();
+        assert_eq!(fs.read(Path::new("/c/a/b/two.txt"), 10).unwrap(), b"2");
+        fs.rename(Path::new("/a"), Path::new("/d")).unwrap();
+        assert!(fs.metadata(Path::new("/a")).is_err());
+        assert!(fs.metadata(Path::new("/d/b/two.txt")).unwrap().is_file());
+    }
+
+    #[test]
+    fn refuses_to_move_into_itself() {
+        let fs = tree();
+        let before = paths(&fs);
+        assert!(fs.rename(Path::new("/a"), Path::new("/a/b/a")).is_err());
+        assert!(fs.copy(Path::new("/a"), Path::new("/a/b/a")).is_err());
+        assert_eq!(paths(&fs), before);
+    }
+
+    #[test]
+    fn refuses_destinations_without_a_parent() {
+        let fs = tree();
+        assert!(fs.rename(Path::new("/c"), Path::new("/missing/c")).is_err());
+        assert!(fs
+            .copy(Path::new("/a/one.txt"), Path::new("/a/one.txt/x"))
+            .is_err());
+        assert!(fs.rename(Path::new("/a/one.txt"), Path::new("/c")).is_err());
+    }
+
+    /// An empty directory under the system's temporary directory
+    pub(in crate::components::explorer) fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tui-components-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_links_as_links() {
+        use std::os::unix::fs::symlink;
+        let dir = scratch_dir("copy-links");
+        fs::create_dir(dir.join("a")).unwrap();
+        fs::write(dir.join("a/file.txt"), "1").unwrap();
+        // following this would copy forever
+        symlink("..", dir.join("a/up")).unwrap();
+        StdFileSystem.copy(&dir.join("a"), &dir.join("b")).unwrap();
+        assert!(fs::symlink_metadata(dir.join("b/up"))
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_link(dir.join("b/up")).unwrap(), Path::new(".."));
+        assert_eq!(fs::read_to_string(dir.join("b/file.txt")).unwrap(), "1");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn removes_partial_copies() {
+        let dir = scratch_dir("partial-copy");
+        fs::create_dir(dir.join("a")).unwrap();
+        fs::write(dir.join("a/file.txt"), "1").unwrap();
+        // sockets can't be opened, so copying one fails
+        let _socket = std::os::unix::net::UnixListener::bind(dir.join("a/socket")).unwrap();
+        assert!(StdFileSystem.copy(&dir.join("a"), &dir.join("b")).is_err());
+        assert!(fs::symlink_metadata(dir.join("b")).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
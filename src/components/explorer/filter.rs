@@ -0,0 +1,133 @@
+use regex::Regex;
+use std::fmt::{self, Debug};
+use std::path::Path;
+use std::sync::Arc;
+
+pub type FilterFn = dyn Fn(&Path) -> bool + Send + Sync;
+
+/// Limits the files listed by an [`Explorer`](crate::components::Explorer).
+///
+/// Directories are always listed, so that they can still be browsed
+#[derive(Clone)]
+pub struct FileFilter {
+    name: String,
+    kind: FilterKind,
+}
+
+#[derive(Clone)]
+enum FilterKind {
+    Glob(Vec<Regex>),
+    Extensions(Vec<String>),
+    Predicate(Arc<FilterFn>),
+}
+
+impl FileFilter {
+    /// Matches file names against glob patterns, such as `*.json` or `log-??.txt`
+    pub fn glob<N: Into<String>>(name: N, patterns: &[&str]) -> Self {
+        Self {
+            name: name.into(),
+            kind: FilterKind::Glob(patterns.iter().map(|p| glob_to_regex(p)).collect()),
+        }
+    }
+
+    /// Matches files by extension, ignoring case and any leading dot
+    pub fn extensions<N: Into<String>>(name: N, extensions: &[&str]) -> Self {
+        Self {
+            name: name.into(),
+            kind: FilterKind::Extensions(
+                extensions
+                    .iter()
+                    .map(|e| e.trim_start_matches('.').to_lowercase())
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn predicate<N: Into<String>, F: Fn(&Path) -> bool + Send + Sync + 'static>(
+        name: N,
+        f: F,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind: FilterKind::Predicate(Arc::new(f)),
+        }
+    }
+
+    /// The name shown in the explorer's header
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        match &self.kind {
+            FilterKind::Glob(patterns) => patterns.iter().any(|p| p.is_match(&file_name)),
+            FilterKind::Extensions(extensions) => path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .is_some_and(|ext| extensions.contains(&ext)),
+            FilterKind::Predicate(f) => f(path),
+        }
+    }
+}
+
+impl Debug for FileFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileFilter")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Translates a glob pattern into an anchored regex
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    pattern.push('$');
+    // every other character is escaped, so the pattern is always valid
+    Regex::new(&pattern).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_globs() {
+        let regex = glob_to_regex("log-??.*");
+        assert!(regex.is_match("log-01.txt"));
+        assert!(!regex.is_match("log-1.txt"));
+        assert!(!regex.is_match("old-log-01.txt"));
+        // regex syntax in the glob is taken literally
+        let regex = glob_to_regex("a+(b).[c]");
+        assert!(regex.is_match("a+(b).[c]"));
+        assert!(!regex.is_match("aa(b)x[c]"));
+    }
+
+    #[test]
+    fn matches_file_names() {
+        let json = FileFilter::glob("JSON", &["*.json", "*.jsonc"]);
+        assert!(json.matches(Path::new("/dir/a.json")));
+        assert!(json.matches(Path::new("b.jsonc")));
+        assert!(!json.matches(Path::new("/dir.json/a.txt")));
+    }
+
+    #[test]
+    fn matches_extensions_ignoring_case_and_dots() {
+        let images = FileFilter::extensions("Images", &[".PNG", "jpg"]);
+        assert!(images.matches(Path::new("/a/photo.png")));
+        assert!(images.matches(Path::new("photo.JPG")));
+        assert!(!images.matches(Path::new("photo.jpeg")));
+        assert!(!images.matches(Path::new("png")));
+        assert!(!images.matches(Path::new(".png")));
+    }
+}
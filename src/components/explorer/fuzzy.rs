@@ -0,0 +1,62 @@
+/// Matches the characters of `pattern` in order within `text`, ignoring case.
+///
+/// Returns a score, higher being a better match, and the char indices matched
+pub(super) fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let text_chars = text.chars().collect::<Vec<_>>();
+    let lower = text_chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect::<Vec<_>>();
+    let mut positions: Vec<usize> = Vec::new();
+    let mut score = 0;
+    let mut start = 0;
+    for p in pattern.chars().flat_map(char::to_lowercase) {
+        let index = start + lower[start..].iter().position(|c| *c == p)?;
+        score += 1;
+        match positions.last() {
+            // consecutive characters are the strongest signal
+            Some(last) if *last + 1 == index => score += 8,
+            Some(last) => score -= (index - last - 1).min(5) as i64,
+            None => score -= index.min(5) as i64,
+        }
+        if is_word_start(&text_chars, index) {
+            score += 6;
+        }
+        positions.push(index);
+        start = index + 1;
+    }
+    // prefer shorter names when the rest is equal
+    score -= (text_chars.len() as i64 - positions.len() as i64).min(10) / 2;
+    Some((score, positions))
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    match index.checked_sub(1).map(|i| chars[i]) {
+        None => true,
+        Some(previous) => {
+            !previous.is_alphanumeric() || (previous.is_lowercase() && chars[index].is_uppercase())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_in_order_ignoring_case() {
+        assert_eq!(fuzzy_match("fb", "Foo_Bar").unwrap().1, [0, 4]);
+        assert_eq!(fuzzy_match("BAR", "foobar").unwrap().1, [3, 4, 5]);
+        assert!(fuzzy_match("bf", "foo_bar").is_none());
+        assert!(fuzzy_match("x", "").is_none());
+    }
+
+    #[test]
+    fn prefers_consecutive_and_word_start_matches() {
+        let score = |pattern, text| fuzzy_match(pattern, text).unwrap().0;
+        assert!(score("abc", "abc.txt") > score("abc", "a_b_c.txt"));
+        assert!(score("mf", "my_file") > score("mf", "lmfao"));
+        assert!(score("mf", "myFile") > score("mf", "myfile"));
+        assert!(score("read", "readme") > score("read", "readme.markdown"));
+    }
+}
@@ -0,0 +1,67 @@
+use super::{EntryInfo, FileSystem};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// The entries are handed over in batches, to keep locking rare
+const BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Default)]
+struct Listing {
+    entries: Vec<EntryInfo>,
+    /// set once the listing is complete, or failed
+    result: Option<Result<(), String>>,
+}
+
+/// Lists a directory on a background thread
+#[derive(Debug, Clone)]
+pub(super) struct Loader {
+    listing: Arc<Mutex<Listing>>,
+    cancelled: Arc<AtomicBool>,
+    /// how many entries this copy has taken, so clones each see every entry
+    taken: usize,
+}
+
+impl Loader {
+    pub(super) fn spawn(fs: Arc<dyn FileSystem>, path: PathBuf) -> Self {
+        let listing = Arc::new(Mutex::new(Listing::default()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let loader = Self {
+            listing: listing.clone(),
+            cancelled: cancelled.clone(),
+            taken: 0,
+        };
+        thread::spawn(move || {
+            let mut batch = Vec::new();
+            let result = fs.visit_dir(&path, &mut |path, meta| {
+                batch.push(EntryInfo { path, meta });
+                if batch.len() >= BATCH_SIZE {
+                    listing.lock().unwrap().entries.append(&mut batch);
+                }
+                !cancelled.load(Ordering::Relaxed)
+            });
+            let mut listing = listing.lock().unwrap();
+            listing.entries.append(&mut batch);
+            listing.result = Some(result.map_err(|e| format!("{}", e)));
+        });
+        loader
+    }
+
+    /// Stops the listing early. Entries already loaded are kept
+    pub(super) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Takes the entries loaded since the last call, and the result once listing is done
+    pub(super) fn take(&mut self) -> (Vec<EntryInfo>, Option<Result<(), String>>) {
+        let listing = self.listing.lock().unwrap();
+        let entries = listing.entries[self.taken..].to_vec();
+        self.taken = listing.entries.len();
+        (entries, listing.result.clone())
+    }
+
+    pub(super) fn loaded(&self) -> usize {
+        self.taken
+    }
+}
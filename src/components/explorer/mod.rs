@@ -0,0 +1,2051 @@
+#[cfg(feature = "archives")]
+mod archive;
+mod columns;
+mod completer;
+mod file_system;
+mod filter;
+mod fuzzy;
+mod loader;
+mod operations;
+mod places;
+mod preview;
+mod tree;
+mod watch;
+
+#[cfg(feature = "archives")]
+pub use archive::*;
+pub use columns::*;
+pub use completer::PathCompleter;
+pub use file_system::*;
+pub use filter::*;
+pub use operations::*;
+pub use watch::WatchMode;
+
+use crate::components::{
+    Breadcrumb, BreadcrumbResponse, Confirm, ConfirmResponse, Input, InputResponse,
+};
+use crate::rect_ext::RectExt;
+use crate::{Component, Event};
+use completer::{is_path, resolve};
+use crossterm::event::{KeyCode, KeyModifiers};
+use fuzzy::fuzzy_match;
+use loader::Loader;
+use places::{Place, PlaceKind, RECENT_CAPACITY};
+use preview::Preview;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tui::layout::{Alignment, Constraint, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{
+    Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, StatefulWidget, Table,
+    TableState, Widget,
+};
+use tui::{
+    buffer::Buffer,
+    layout::{Direction, Layout},
+};
+use watch::Watcher;
+
+const COLUMN_SPACING: u16 = 2;
+
+#[derive(Debug, Clone)]
+pub struct Explorer {
+    fs: Arc<dyn FileSystem>,
+    path: PathBuf,
+    input: Input,
+    input_active: bool,
+    /// every entry in the directory
+    entries: Result<Vec<EntryInfo>, String>,
+    /// the entries shown, after hiding and filtering
+    files: Result<Vec<EntryInfo>, String>,
+    mode: ExplorerMode,
+    /// used to confirm overwriting an existing file, or deleting one
+    confirm: Option<(Confirm, PendingConfirm)>,
+    table_state: TableState,
+    show_hidden: bool,
+    filters: Vec<FileFilter>,
+    /// the active filter, with one past the end meaning all files
+    filter_index: usize,
+    columns: Vec<ExplorerColumn>,
+    sort: SortOrder,
+    /// narrows and ranks the files by the search, rather than jumping to a prefix
+    fuzzy: bool,
+    /// the char indices of each listed name matching the search
+    highlights: Vec<Vec<usize>>,
+    /// allows choosing several files in open mode
+    multi_select: bool,
+    /// the chosen files, in the order they were chosen
+    selection: Vec<PathBuf>,
+    /// the index a shift selection extends from
+    anchor: Option<usize>,
+    /// shows the highlighted entry's contents beside the list
+    preview: bool,
+    /// the preview of the last highlighted entry, loaded when drawn
+    preview_cache: Option<(PathBuf, Preview)>,
+    /// allows creating, renaming, deleting, copying and moving entries
+    operations: bool,
+    /// asks for the name of a new or renamed entry
+    prompt: Option<(NamePrompt, Input)>,
+    /// the path cut or copied, and whether it's moved rather than copied
+    cut_buffer: Option<(PathBuf, bool)>,
+    /// shown in place of the search until the next key
+    error: Option<String>,
+    /// lists directories on a background thread, collected by [`Explorer::poll`]
+    background: bool,
+    loader: Option<Loader>,
+    /// the directory, entries and index to return to if loading is cancelled
+    previous: Option<(PathBuf, Result<Vec<EntryInfo>, String>, usize)>,
+    /// the entries of a background refresh, replacing the listing once complete
+    refreshing: Option<Vec<EntryInfo>>,
+    watcher: Watcher,
+    bookmarks: Vec<PathBuf>,
+    /// where bookmarks are saved after each change
+    bookmarks_file: Option<PathBuf>,
+    /// directories visited, most recent first
+    recent: VecDeque<PathBuf>,
+    /// the places popup, opened with `b`
+    places: Option<(Vec<Place>, ListState)>,
+    /// directories to return to with Alt-Left, most recent last
+    back: Vec<PathBuf>,
+    /// directories left with Alt-Left, to return to with Alt-Right
+    forward: Vec<PathBuf>,
+    /// the entry last highlighted in each directory visited
+    selections: HashMap<PathBuf, PathBuf>,
+    /// the entry to highlight once it's loaded in the background
+    restore: Option<PathBuf>,
+    /// the history to return to if loading is cancelled
+    previous_history: Option<(Vec<PathBuf>, Vec<PathBuf>)>,
+    /// lists the children of expanded directories inline
+    tree: bool,
+    /// the entries of each expanded directory, loaded when expanded
+    expanded: HashMap<PathBuf, Vec<EntryInfo>>,
+    /// the indentation guides before each listed name, in tree mode
+    guides: Vec<String>,
+    /// the path shown above the listing, focused with `g` to jump to an ancestor
+    breadcrumb: Breadcrumb,
+    /// the directory is listed when first used, once background loading is known
+    unlisted: bool,
+}
+
+#[derive(Debug, Clone)]
+enum PendingConfirm {
+    Overwrite(PathBuf),
+    Operation(FileOperation),
+}
+
+#[derive(Debug, Clone)]
+enum NamePrompt {
+    Directory,
+    File,
+    Rename(PathBuf),
+}
+
+impl NamePrompt {
+    fn title(&self) -> &'static str {
+        match self {
+            NamePrompt::Directory => "New directory",
+            NamePrompt::File => "New file",
+            NamePrompt::Rename(_) => "Rename",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplorerMode {
+    Open,
+    Save,
+    /// lists directories only. `c` chooses the highlighted directory, and `C` the current one
+    SelectDirectory,
+}
+
+#[derive(Debug, Clone)]
+struct EntryInfo {
+    path: PathBuf,
+    meta: EntryMeta,
+}
+
+impl Explorer {
+    pub fn new<P: AsRef<Path>>(path: P, mode: ExplorerMode) -> Self {
+        Self::with_file_system(path, mode, StdFileSystem)
+    }
+
+    /// Creates an explorer browsing a file system other than the local one
+    pub fn with_file_system<P: AsRef<Path>, F: FileSystem + 'static>(
+        path: P,
+        mode: ExplorerMode,
+        fs: F,
+    ) -> Self {
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+        let mut explorer = Explorer {
+            fs: Arc::new(fs),
+            path: path.as_ref().to_path_buf(),
+            input: Input::default().editing_style(Style::default().bg(Color::Blue)),
+            input_active: false,
+            entries: Ok(Vec::new()),
+            files: Ok(Vec::new()),
+            mode,
+            confirm: None,
+            table_state,
+            show_hidden: true,
+            filters: Vec::new(),
+            filter_index: 0,
+            columns: vec![ExplorerColumn::Name],
+            sort: SortOrder::default(),
+            fuzzy: false,
+            highlights: Vec::new(),
+            multi_select: false,
+            selection: Vec::new(),
+            anchor: None,
+            preview: false,
+            preview_cache: None,
+            operations: false,
+            prompt: None,
+            cut_buffer: None,
+            error: None,
+            background: false,
+            loader: None,
+            previous: None,
+            refreshing: None,
+            watcher: Watcher::new(WatchMode::Off),
+            bookmarks: Vec::new(),
+            bookmarks_file: None,
+            recent: VecDeque::new(),
+            places: None,
+            back: Vec::new(),
+            forward: Vec::new(),
+            selections: HashMap::new(),
+            restore: None,
+            previous_history: None,
+            tree: false,
+            expanded: HashMap::new(),
+            guides: Vec::new(),
+            breadcrumb: Breadcrumb::new(&path),
+            unlisted: true,
+        };
+        explorer.update_completer();
+        explorer.visit();
+        explorer
+    }
+
+    /// Whether to list dotfiles. They can also be toggled with `.`
+    pub fn show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self.update_files();
+        self
+    }
+
+    /// Adds a filter, selectable with Tab. The first filter added is active initially
+    pub fn filter(mut self, filter: FileFilter) -> Self {
+        self.filters.push(filter);
+        self.update_files();
+        self
+    }
+
+    /// Sets the columns shown, with the name always shown first
+    pub fn columns(mut self, columns: &[ExplorerColumn]) -> Self {
+        self.columns = vec![ExplorerColumn::Name];
+        self.columns.extend(
+            columns
+                .iter()
+                .filter(|column| **column != ExplorerColumn::Name),
+        );
+        self
+    }
+
+    /// Sets the initial ordering. The key is cycled with `s`, and reversed with `r`
+    pub fn sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self.update_files();
+        self
+    }
+
+    /// Whether searching with `/` narrows the list to fuzzy matches, ranked by score.
+    /// Otherwise, the search jumps to the first name starting with it
+    pub fn fuzzy(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self.update_files();
+        self
+    }
+
+    /// Whether several files can be chosen in open mode, returning
+    /// [`ExplorerResponse::OpenMany`]. Space toggles a file, `a` toggles every listed file,
+    /// and Shift with Up or Down selects a range
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+
+    /// Whether to show a preview of the highlighted entry. It can also be toggled with `p`
+    pub fn preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// Whether entries can be changed, each key emitting an [`ExplorerResponse::Operation`]:
+    /// F7 creates a directory, Ctrl-N creates a file, F2 renames, Delete deletes,
+    /// Ctrl-X and Ctrl-C cut and copy, and Ctrl-V pastes into the current directory
+    pub fn file_operations(mut self, operations: bool) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Performs an operation emitted by the explorer, then reloads the directory.
+    /// Operations that aren't applied are vetoed.
+    ///
+    /// Errors are also shown in the explorer
+    pub fn apply_operation(&mut self, operation: &FileOperation) -> Result<(), String> {
+        let result = operation
+            .apply(self.fs.as_ref())
+            .map_err(|e| format!("{}", e));
+        match &result {
+            Ok(()) => {
+                if let FileOperation::Move { from, .. } = operation {
+                    if self
+                        .cut_buffer
+                        .as_ref()
+                        .is_some_and(|(path, _)| path == from)
+                    {
+                        self.cut_buffer = None;
+                    }
+                }
+            }
+            Err(e) => self.error = Some(e.clone()),
+        }
+        self.unlisted = false;
+        self.entries = self.get_files(&self.path);
+        self.reload_expanded();
+        self.preview_cache = None;
+        self.update_files();
+        self.watcher.watch(self.fs.as_ref(), &self.path);
+        if let (Some(target), Ok(files)) = (operation.target(), &self.files) {
+            if let Some(index) = files.iter().position(|entry| entry.path == target) {
+                self.table_state.select(Some(index));
+            }
+        }
+        result
+    }
+
+    /// Whether directories navigated to are listed on a background thread, showing entries
+    /// as they're read. Esc cancels a listing, returning to the previous directory.
+    ///
+    /// The host must call [`Explorer::poll`] regularly, such as from [`App::tick`](crate::App::tick)
+    pub fn background_loading(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Whether directories are listed as a tree, expanding inline with Right and collapsing
+    /// with Left. It can also be toggled with `t`
+    pub fn tree(mut self, tree: bool) -> Self {
+        self.tree = tree;
+        self.update_files();
+        self
+    }
+
+    /// Sets how changes to the listed directory are noticed, refreshing the listing.
+    /// F5 always refreshes
+    pub fn watch(mut self, mode: WatchMode) -> Self {
+        self.watcher = Watcher::new(mode);
+        self.watcher.watch(self.fs.as_ref(), &self.path);
+        self
+    }
+
+    /// Loads bookmarks from a file, saving them back to it whenever they change.
+    /// A missing file is created on the first change
+    pub fn bookmarks_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        match places::load_bookmarks(path.as_ref()) {
+            Ok(bookmarks) => {
+                for bookmark in bookmarks {
+                    if !self.bookmarks.contains(&bookmark) {
+                        self.bookmarks.push(bookmark);
+                    }
+                }
+            }
+            Err(e) => self.error = Some(format!("{}: {}", path.as_ref().display(), e)),
+        }
+        self.bookmarks_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Adds a bookmark. `b` lists the bookmarks with other places to jump to,
+    /// `B` bookmarks the current directory, and `1` to `9` jump to a bookmark
+    pub fn bookmark<P: AsRef<Path>>(mut self, path: P) -> Self {
+        if !self.bookmarks.iter().any(|b| b == path.as_ref()) {
+            self.bookmarks.push(path.as_ref().to_path_buf());
+        }
+        self
+    }
+
+    pub fn bookmarks(&self) -> &[PathBuf] {
+        &self.bookmarks
+    }
+
+    /// Lists the directory again, keeping the selected entry where possible
+    pub fn refresh(&mut self) {
+        self.unlisted = false;
+        if self.background {
+            if self.loader.is_none() {
+                self.loader = Some(Loader::spawn(self.fs.clone(), self.path.clone()));
+                self.refreshing = Some(Vec::new());
+            }
+        } else {
+            self.entries = self.get_files(&self.path);
+            self.reload_expanded();
+            self.preview_cache = None;
+            self.update_files();
+        }
+        self.watcher.watch(self.fs.as_ref(), &self.path);
+    }
+
+    /// Collects entries listed in the background, and refreshes the listing if the
+    /// directory is watched and has changed. Returns whether anything changed
+    pub fn poll(&mut self) -> bool {
+        if self.unlisted {
+            self.list();
+            return true;
+        }
+        if self.loader.is_none() && self.watcher.changed(self.fs.as_ref(), &self.path) {
+            self.refresh();
+            if !self.background {
+                return true;
+            }
+        }
+        let loader = match &mut self.loader {
+            Some(loader) => loader,
+            None => return false,
+        };
+        let (new, result) = loader.take();
+        let mut changed = !new.is_empty() || result.is_some();
+        // a refresh keeps the selection, even once it completes
+        let refreshing = self.refreshing.is_some();
+        match &mut self.refreshing {
+            Some(refreshed) => {
+                // the old listing stays until the new one is complete
+                refreshed.extend(new);
+                changed = result.is_some();
+            }
+            None => {
+                if let Ok(entries) = &mut self.entries {
+                    entries.extend(new);
+                }
+            }
+        }
+        if let Some(result) = result {
+            if let (Err(e), Some(_)) = (&result, &self.previous) {
+                let message = format!("{}: {}", self.path.display(), e);
+                self.cancel_loading();
+                self.error = Some(message);
+                return true;
+            }
+            self.entries = match (result, self.refreshing.take()) {
+                (Err(e), _) => Err(e),
+                (Ok(()), Some(refreshed)) => Ok(refreshed),
+                (Ok(()), None) => std::mem::replace(&mut self.entries, Ok(Vec::new())),
+            };
+            self.reload_expanded();
+            self.loader = None;
+            self.previous = None;
+            self.previous_history = None;
+            self.preview_cache = None;
+        }
+        if changed {
+            // stay at the top while entries arrive, unless the user has moved
+            let at_top = self.index() == 0 && !refreshing;
+            self.update_files();
+            if at_top {
+                self.table_state.select(Some(0));
+                self.select_restored();
+            }
+        }
+        if self.loader.is_none() {
+            self.restore = None;
+        }
+        changed
+    }
+
+    /// Lists the directory for the first time
+    fn list(&mut self) {
+        self.unlisted = false;
+        if self.background {
+            self.loader = Some(Loader::spawn(self.fs.clone(), self.path.clone()));
+        } else {
+            self.entries = self.get_files(&self.path);
+            self.update_files();
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loader.is_some()
+    }
+
+    /// The files chosen so far, in the order they were chosen
+    pub fn selection(&self) -> &[PathBuf] {
+        &self.selection
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+    }
+
+    /// The active filter, or `None` if all files are shown
+    pub fn active_filter(&self) -> Option<&FileFilter> {
+        self.filters.get(self.filter_index)
+    }
+
+    fn get_files<P: AsRef<Path>>(&self, path: P) -> Result<Vec<EntryInfo>, String> {
+        self.fs
+            .read_dir(path.as_ref())
+            .map_err(|e| format!("{}", e))
+            .map(|entries| {
+                entries
+                    .into_iter()
+                    .map(|(path, meta)| EntryInfo { path, meta })
+                    .collect()
+            })
+    }
+
+    /// Recomputes the listed files, keeping the selected entry where possible
+    fn update_files(&mut self) {
+        let selected = self.selected_path().map(|entry| entry.path.clone());
+        // the rows the anchor pointed at may have moved
+        self.anchor = None;
+        self.guides.clear();
+        self.files = match self.entries.clone() {
+            Ok(entries) if self.tree => {
+                let (files, guides) =
+                    tree::flatten(entries, &self.expanded, &|entries| self.prepare(entries));
+                self.guides = guides;
+                Ok(files)
+            }
+            entries => entries.map(|entries| self.prepare(entries)),
+        };
+        self.highlights.clear();
+        // a typed path is for navigating, rather than searching
+        let query = if is_path(&self.input.value) {
+            String::new()
+        } else {
+            self.input.value.to_lowercase()
+        };
+        if let Ok(files) = &mut self.files {
+            if query.is_empty() {
+                self.highlights = vec![Vec::new(); files.len()];
+            } else if self.fuzzy && self.tree {
+                // ranking would break up the tree, so matches are only highlighted
+                self.highlights = files
+                    .iter()
+                    .map(|entry| {
+                        fuzzy_match(&query, &name_of(entry))
+                            .map_or_else(Vec::new, |(_, positions)| positions)
+                    })
+                    .collect();
+            } else if self.fuzzy {
+                let mut ranked = files
+                    .drain(..)
+                    .filter_map(|entry| {
+                        fuzzy_match(&query, &name_of(&entry))
+                            .map(|(score, positions)| (score, positions, entry))
+                    })
+                    .collect::<Vec<_>>();
+                // stable, so equal scores keep the sort order
+                ranked.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+                for (_, positions, entry) in ranked {
+                    self.highlights.push(positions);
+                    files.push(entry);
+                }
+            } else {
+                let len = query.chars().count();
+                self.highlights = files
+                    .iter()
+                    .map(|entry| {
+                        if name_of(entry).to_lowercase().starts_with(&query) {
+                            (0..len).collect()
+                        } else {
+                            Vec::new()
+                        }
+                    })
+                    .collect();
+            }
+        }
+        let index = match (&self.files, selected) {
+            (Ok(files), Some(selected)) => files
+                .iter()
+                .position(|entry| entry.path == selected)
+                .unwrap_or_else(|| self.index().min(files.len().saturating_sub(1))),
+            _ => 0,
+        };
+        self.table_state.select(Some(index));
+    }
+
+    /// Hides and filters a directory's entries, then sorts them
+    fn prepare(&self, entries: Vec<EntryInfo>) -> Vec<EntryInfo> {
+        let filter = self.active_filter();
+        let mut files = entries
+            .into_iter()
+            .filter(|entry| {
+                self.show_hidden
+                    || !entry
+                        .path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            })
+            .filter(|entry| {
+                entry.meta.resolves_to_dir() || filter.is_none_or(|f| f.matches(&entry.path))
+            })
+            .filter(|entry| {
+                self.mode != ExplorerMode::SelectDirectory || entry.meta.resolves_to_dir()
+            })
+            .collect::<Vec<_>>();
+        self.sort.sort(&mut files);
+        files
+    }
+
+    /// Lists the highlighted directory's entries below it, or moves into them if it's
+    /// already expanded
+    fn expand(&mut self) {
+        let path = match self.selected_path() {
+            Some(entry) if entry.meta.resolves_to_dir() => entry.path.clone(),
+            _ => return,
+        };
+        if self.expanded.contains_key(&path) {
+            let next = self.index() + 1;
+            let has_children = self
+                .files
+                .as_ref()
+                .ok()
+                .and_then(|files| files.get(next))
+                .is_some_and(|entry| entry.path.parent() == Some(path.as_path()));
+            if has_children {
+                self.table_state.select(Some(next));
+            }
+            return;
+        }
+        match self.get_files(&path) {
+            Ok(children) => {
+                self.expanded.insert(path, children);
+                self.update_files();
+            }
+            Err(e) => self.error = Some(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Collapses the highlighted directory, or moves to the directory containing the entry
+    fn collapse(&mut self) {
+        let path = match self.selected_path() {
+            Some(entry) => entry.path.clone(),
+            None => return,
+        };
+        if self.expanded.contains_key(&path) {
+            self.expanded
+                .retain(|expanded, _| !expanded.starts_with(&path));
+            self.update_files();
+        } else if let (Some(parent), Ok(files)) = (path.parent(), &self.files) {
+            if let Some(index) = files.iter().position(|entry| entry.path == parent) {
+                self.table_state.select(Some(index));
+            }
+        }
+    }
+
+    fn toggle_tree(&mut self) {
+        self.tree = !self.tree;
+        // an entry within an expanded directory isn't listed without the tree
+        let top = self.selected_path().and_then(|entry| {
+            entry
+                .path
+                .ancestors()
+                .find(|path| path.parent() == Some(self.path.as_path()))
+                .map(Path::to_path_buf)
+        });
+        self.update_files();
+        if let (Some(top), Ok(files)) = (top, &self.files) {
+            if let Some(index) = files.iter().position(|entry| entry.path == top) {
+                self.table_state.select(Some(index));
+            }
+        }
+    }
+
+    /// Lists the expanded directories again, collapsing those that can't be read
+    fn reload_expanded(&mut self) {
+        let paths = self.expanded.keys().cloned().collect::<Vec<_>>();
+        for path in paths {
+            match self.get_files(&path) {
+                Ok(children) => {
+                    self.expanded.insert(path, children);
+                }
+                Err(_) => {
+                    self.expanded.remove(&path);
+                }
+            }
+        }
+    }
+
+    /// Goes to a directory, recording the current one in the history
+    fn set_path<P: AsRef<Path>>(&mut self, path: P) {
+        self.save_history();
+        let from = self.path.clone();
+        if self.load_path(&path) && from != self.path {
+            self.back.push(from);
+            self.forward.clear();
+        }
+    }
+
+    /// Returns to the previous directory in the history
+    fn go_back(&mut self) {
+        self.save_history();
+        if let Some(path) = self.back.pop() {
+            let from = self.path.clone();
+            if self.load_path(&path) {
+                self.forward.push(from);
+            } else {
+                self.back.push(path);
+            }
+        }
+    }
+
+    /// Returns to the directory left by going back
+    fn go_forward(&mut self) {
+        self.save_history();
+        if let Some(path) = self.forward.pop() {
+            let from = self.path.clone();
+            if self.load_path(&path) {
+                self.back.push(from);
+            } else {
+                self.forward.push(path);
+            }
+        }
+    }
+
+    /// Keeps the history from before a background load, in case it's cancelled
+    fn save_history(&mut self) {
+        if self.background && self.previous.is_none() {
+            self.previous_history = Some((self.back.clone(), self.forward.clone()));
+        }
+    }
+
+    /// Lists a directory, highlighting the entry last highlighted there, or the directory
+    /// just left if it's a child. Returns whether the directory changed
+    fn load_path<P: AsRef<Path>>(&mut self, path: P) -> bool {
+        if let Some(entry) = self.selected_path() {
+            let selected = entry.path.clone();
+            self.selections.insert(self.path.clone(), selected);
+        }
+        let restore = match self.selections.get(path.as_ref()) {
+            Some(selected) => Some(selected.clone()),
+            None if self.fs.parent(&self.path).as_deref() == Some(path.as_ref()) => {
+                Some(self.path.clone())
+            }
+            // the directory leading back to where we were
+            None if self.path.starts_with(&path) => self
+                .path
+                .ancestors()
+                .find(|ancestor| ancestor.parent() == Some(path.as_ref()))
+                .map(Path::to_path_buf),
+            None => None,
+        };
+        if self.background {
+            if let Some(loader) = self.loader.take() {
+                loader.cancel();
+            }
+            // keep returning to the last directory that finished loading
+            if self.previous.is_none() {
+                let entries = std::mem::replace(&mut self.entries, Ok(Vec::new()));
+                self.previous = Some((self.path.clone(), entries, self.index()));
+            }
+            self.refreshing = None;
+            self.entries = Ok(Vec::new());
+            self.loader = Some(Loader::spawn(self.fs.clone(), path.as_ref().to_path_buf()));
+        } else {
+            match self.get_files(&path) {
+                // stay in the current directory rather than losing it to the error
+                Err(e) if self.entries.is_ok() => {
+                    self.error = Some(format!("{}: {}", path.as_ref().display(), e));
+                    return false;
+                }
+                listing => self.entries = listing,
+            }
+        }
+        if self.fuzzy {
+            // the search narrows the list, so it would hide the new directory's files
+            self.input.value.clear();
+        }
+        self.path = path.as_ref().to_path_buf();
+        self.expanded.clear();
+        self.preview_cache = None;
+        self.table_state.select(Some(0));
+        self.update_files();
+        self.restore = restore;
+        self.select_restored();
+        if !self.background {
+            self.restore = None;
+        }
+        self.watcher.watch(self.fs.as_ref(), &self.path);
+        self.update_completer();
+        self.visit();
+        true
+    }
+
+    /// Completes typed paths relative to the current directory
+    fn update_completer(&mut self) {
+        let completer = PathCompleter::with_file_system(&self.path, self.fs.clone())
+            .directories_only(self.mode == ExplorerMode::SelectDirectory);
+        self.input.set_completer(completer);
+    }
+
+    /// Goes to a typed directory, or opens or saves a typed file
+    fn submit_path(&mut self) -> ExplorerResponse {
+        let path = resolve(&self.path, self.input.value.trim());
+        match self.fs.metadata(&path) {
+            Ok(meta) if meta.is_dir() => {
+                self.input.value.clear();
+                self.input_active = false;
+                self.update_files();
+                self.set_path(path);
+                ExplorerResponse::Handled
+            }
+            Ok(_) => match self.mode {
+                ExplorerMode::Open if self.multi_select => {
+                    let mut paths = std::mem::take(&mut self.selection);
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                    ExplorerResponse::OpenMany(paths)
+                }
+                ExplorerMode::Open => ExplorerResponse::Open(path),
+                ExplorerMode::Save => {
+                    self.confirm = Some((
+                        Confirm::new("Overwrite file?"),
+                        PendingConfirm::Overwrite(path),
+                    ));
+                    ExplorerResponse::Handled
+                }
+                ExplorerMode::SelectDirectory => {
+                    // the error is shown in place of the input
+                    self.input_active = false;
+                    self.error = Some(format!("Not a directory: {}", path.display()));
+                    ExplorerResponse::Handled
+                }
+            },
+            // a new file can be saved into an existing directory
+            Err(_)
+                if self.mode == ExplorerMode::Save
+                    && self.fs.parent(&path).is_some_and(|parent| {
+                        self.fs.metadata(&parent).is_ok_and(|m| m.is_dir())
+                    }) =>
+            {
+                ExplorerResponse::Save(path)
+            }
+            Err(e) => {
+                self.input_active = false;
+                self.error = Some(format!("{}: {}", path.display(), e));
+                ExplorerResponse::Handled
+            }
+        }
+    }
+
+    /// Highlights the entry to restore, once it's listed
+    fn select_restored(&mut self) {
+        let index = match (&self.restore, &self.files) {
+            (Some(restore), Ok(files)) => files.iter().position(|entry| entry.path == *restore),
+            _ => None,
+        };
+        if let Some(index) = index {
+            self.table_state.select(Some(index));
+            self.restore = None;
+        }
+    }
+
+    /// Records the current directory as the most recent
+    fn visit(&mut self) {
+        self.recent.retain(|path| *path != self.path);
+        self.recent.push_front(self.path.clone());
+        self.recent.truncate(RECENT_CAPACITY);
+    }
+
+    /// Bookmarks the current directory, or removes its bookmark
+    fn toggle_bookmark(&mut self) {
+        match self.bookmarks.iter().position(|b| *b == self.path) {
+            Some(i) => {
+                self.bookmarks.remove(i);
+            }
+            None => self.bookmarks.push(self.path.clone()),
+        }
+        self.save_bookmarks();
+    }
+
+    fn save_bookmarks(&mut self) {
+        if let Some(file) = &self.bookmarks_file {
+            if let Err(e) = places::save_bookmarks(file, &self.bookmarks) {
+                self.error = Some(format!("{}: {}", file.display(), e));
+            }
+        }
+    }
+
+    fn open_places(&mut self) {
+        let mut state = ListState::default();
+        state.select(Some(0));
+        self.places = Some((places::gather(&self.bookmarks, &self.recent), state));
+    }
+
+    fn handle_places(&mut self, event: Event) -> ExplorerResponse {
+        let key_event = match event {
+            Event::Key(key_event) => key_event,
+            _ => return ExplorerResponse::None,
+        };
+        let (places, state) = match &mut self.places {
+            Some(places) => places,
+            None => return ExplorerResponse::None,
+        };
+        let index = state.selected().unwrap_or(0);
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('b') => self.places = None,
+            KeyCode::Up => {
+                state.select(Some(
+                    index
+                        .checked_sub(1)
+                        .unwrap_or(places.len().saturating_sub(1)),
+                ));
+            }
+            KeyCode::Down => {
+                state.select(Some(if index + 1 >= places.len() {
+                    0
+                } else {
+                    index + 1
+                }));
+            }
+            KeyCode::Enter => {
+                if let Some(place) = places.get(index) {
+                    let path = place.path.clone();
+                    self.places = None;
+                    self.set_path(path);
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(place) = places.get(index) {
+                    if place.kind == PlaceKind::Bookmark {
+                        let path = place.path.clone();
+                        self.bookmarks.retain(|b| *b != path);
+                        self.save_bookmarks();
+                        let index = index.min(self.bookmarks.len().saturating_sub(1));
+                        self.open_places();
+                        if let Some((_, state)) = &mut self.places {
+                            state.select(Some(index));
+                        }
+                    }
+                }
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                self.places = None;
+                self.jump_to_bookmark(c);
+            }
+            _ => {}
+        }
+        ExplorerResponse::Handled
+    }
+
+    fn handle_breadcrumb(&mut self, event: Event) -> ExplorerResponse {
+        match self.breadcrumb.handle_event(event) {
+            BreadcrumbResponse::Jump(path) => {
+                self.breadcrumb.focused = false;
+                self.input_active = false;
+                if path != self.path {
+                    self.set_path(path);
+                }
+                ExplorerResponse::Handled
+            }
+            BreadcrumbResponse::Exit => {
+                self.breadcrumb.focused = false;
+                ExplorerResponse::Handled
+            }
+            BreadcrumbResponse::Handled => ExplorerResponse::Handled,
+            BreadcrumbResponse::None => ExplorerResponse::None,
+        }
+    }
+
+    /// Goes to the bookmark numbered by a digit key, counting from 1
+    fn jump_to_bookmark(&mut self, digit: char) {
+        let index = digit.to_digit(10).unwrap_or(0) as usize;
+        if let Some(path) = index.checked_sub(1).and_then(|i| self.bookmarks.get(i)) {
+            let path = path.clone();
+            self.set_path(path);
+        }
+    }
+
+    fn draw_places(&mut self, rect: Rect, buf: &mut Buffer) {
+        let (places, state) = match &mut self.places {
+            Some(places) => places,
+            None => return,
+        };
+        let items = places
+            .iter()
+            .map(|place| {
+                ListItem::new(Spans::from(vec![
+                    Span::styled(
+                        format!("{:<9}", place.kind.label()),
+                        Style::default().fg(place.kind.color()),
+                    ),
+                    Span::raw(place.path.to_string_lossy().into_owned()),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let block = Block::default()
+            .title(Span::styled("Places", Style::default().fg(Color::White)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: 60.min(rect.width),
+            height: (places.len() as u16 + 2).min(rect.height),
+        });
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(Color::Green));
+        Widget::render(Clear, area, buf);
+        StatefulWidget::render(list, area, buf, state);
+    }
+
+    /// Stops loading a directory, returning to the previous one
+    fn cancel_loading(&mut self) {
+        if let Some(loader) = self.loader.take() {
+            loader.cancel();
+        }
+        self.refreshing = None;
+        self.restore = None;
+        if let Some((path, entries, index)) = self.previous.take() {
+            if let Some((back, forward)) = self.previous_history.take() {
+                self.back = back;
+                self.forward = forward;
+            }
+            self.path = path;
+            self.entries = entries;
+            self.preview_cache = None;
+            self.table_state.select(Some(index));
+            self.update_files();
+            self.watcher.watch(self.fs.as_ref(), &self.path);
+            self.update_completer();
+        }
+    }
+
+    fn cycle_filter(&mut self, forward: bool) {
+        if self.filters.is_empty() {
+            return;
+        }
+        // the extra position is for showing all files
+        let count = self.filters.len() + 1;
+        self.filter_index = if forward {
+            (self.filter_index + 1) % count
+        } else {
+            (self.filter_index + count - 1) % count
+        };
+        self.update_files();
+    }
+
+    /// Moves the selection to the next or previous entry matching the search
+    fn cycle_match(&mut self, forward: bool) {
+        let count = self.highlights.len();
+        let index = self.index();
+        let found = (1..=count)
+            .map(|offset| {
+                if forward {
+                    (index + offset) % count
+                } else {
+                    (index + count - offset % count) % count
+                }
+            })
+            .find(|i| !self.highlights[*i].is_empty());
+        if let Some(i) = found {
+            self.table_state.select(Some(i));
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let path = match self.selected_path() {
+            Some(entry) if !entry.meta.resolves_to_dir() => entry.path.clone(),
+            _ => return,
+        };
+        match self.selection.iter().position(|p| *p == path) {
+            Some(i) => {
+                self.selection.remove(i);
+            }
+            None => self.selection.push(path),
+        }
+    }
+
+    /// Selects every listed file, or deselects them if they are all selected
+    fn toggle_all(&mut self) {
+        let files = match &self.files {
+            Ok(files) => files
+                .iter()
+                .filter(|entry| !entry.meta.resolves_to_dir())
+                .map(|entry| entry.path.clone())
+                .collect::<Vec<_>>(),
+            Err(_) => return,
+        };
+        if files.iter().all(|path| self.selection.contains(path)) {
+            self.selection.retain(|path| !files.contains(path));
+        } else {
+            for path in files {
+                if !self.selection.contains(&path) {
+                    self.selection.push(path);
+                }
+            }
+        }
+    }
+
+    /// Moves the cursor, selecting the files between it and the anchor
+    fn extend_selection(&mut self, forward: bool) {
+        let len = self.files.as_ref().map_or(0, Vec::len);
+        if len == 0 {
+            return;
+        }
+        let anchor = (*self.anchor.get_or_insert(self.index())).min(len - 1);
+        if forward {
+            self.increment();
+        } else {
+            self.decrement();
+        }
+        let index = self.index().min(len - 1);
+        let (start, end) = (anchor.min(index), anchor.max(index));
+        if let Ok(files) = &self.files {
+            for entry in &files[start..=end] {
+                if !entry.meta.resolves_to_dir() && !self.selection.contains(&entry.path) {
+                    self.selection.push(entry.path.clone());
+                }
+            }
+        }
+    }
+
+    fn draw_preview(&mut self, rect: Rect, buf: &mut Buffer) {
+        let path = self.selected_path().map(|entry| entry.path.clone());
+        let block = Block::default()
+            .borders(Borders::LEFT)
+            .border_style(Style::default().fg(Color::Green));
+        let inner = block.inner(rect);
+        Widget::render(block, rect, buf);
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+        if self.preview_cache.as_ref().is_none_or(|(p, _)| *p != path) {
+            let preview = Preview::load(self.fs.as_ref(), &path);
+            self.preview_cache = Some((path, preview));
+        }
+        if let Some((_, preview)) = &self.preview_cache {
+            Widget::render(Paragraph::new(preview.lines()), inner, buf);
+        }
+    }
+
+    fn open_prompt(&mut self, prompt: NamePrompt) {
+        let mut input = Input::default().editing_style(Style::default().bg(Color::Blue));
+        input.focused = true;
+        if let NamePrompt::Rename(path) = &prompt {
+            if let Some(name) = path.file_name() {
+                input.value = name.to_string_lossy().into_owned();
+            }
+        }
+        self.prompt = Some((prompt, input));
+    }
+
+    fn handle_prompt(&mut self, event: Event) -> ExplorerResponse {
+        let (prompt, input) = match &mut self.prompt {
+            Some(prompt) => prompt,
+            None => return ExplorerResponse::None,
+        };
+        match input.handle_event(event) {
+            InputResponse::Submit => {
+                let name = input.value.trim().to_string();
+                let prompt = prompt.clone();
+                self.prompt = None;
+                if name.is_empty() {
+                    return ExplorerResponse::Handled;
+                }
+                if name.chars().any(std::path::is_separator) || name == "." || name == ".." {
+                    self.error = Some(format!("Invalid name: {}", name));
+                    return ExplorerResponse::Handled;
+                }
+                let dir = match &prompt {
+                    NamePrompt::Rename(from) => from.parent().map(Path::to_path_buf),
+                    _ => Some(self.target_dir()),
+                };
+                let path = dir.unwrap_or_else(|| self.path.clone()).join(&name);
+                if matches!(&prompt, NamePrompt::Rename(from) if *from == path) {
+                    return ExplorerResponse::Handled;
+                }
+                ExplorerResponse::Operation(match prompt {
+                    NamePrompt::Directory => FileOperation::CreateDirectory(path),
+                    NamePrompt::File => FileOperation::CreateFile(path),
+                    NamePrompt::Rename(from) => FileOperation::Rename { from, to: path },
+                })
+            }
+            InputResponse::Cancel => {
+                self.prompt = None;
+                ExplorerResponse::Handled
+            }
+            _ => ExplorerResponse::Handled,
+        }
+    }
+
+    /// The directory new entries go in, which in tree mode is the highlighted entry's
+    fn target_dir(&self) -> PathBuf {
+        match self.selected_path().and_then(|entry| entry.path.parent()) {
+            Some(parent) if self.tree => parent.to_path_buf(),
+            _ => self.path.clone(),
+        }
+    }
+
+    fn paste(&mut self) -> ExplorerResponse {
+        let (from, cut) = match &self.cut_buffer {
+            Some((from, cut)) => (from.clone(), *cut),
+            None => return ExplorerResponse::Handled,
+        };
+        let to = match from.file_name() {
+            Some(name) => self.target_dir().join(name),
+            None => return ExplorerResponse::Handled,
+        };
+        ExplorerResponse::Operation(if cut {
+            FileOperation::Move { from, to }
+        } else {
+            FileOperation::Copy { from, to }
+        })
+    }
+
+    fn multi_selecting(&self) -> bool {
+        self.multi_select && self.mode == ExplorerMode::Open
+    }
+
+    fn index(&self) -> usize {
+        self.table_state.selected().unwrap()
+    }
+
+    fn increment(&mut self) {
+        if let Ok(paths) = &self.files {
+            let new = if self.index() >= paths.len().saturating_sub(1) {
+                0
+            } else {
+                self.index() + 1
+            };
+            self.table_state.select(Some(new));
+        }
+    }
+
+    fn decrement(&mut self) {
+        if let Ok(paths) = &self.files {
+            let new = if self.index() == 0 {
+                paths.len().saturating_sub(1)
+            } else {
+                self.index() - 1
+            };
+            self.table_state.select(Some(new));
+        }
+    }
+
+    fn selected_path(&self) -> Option<&EntryInfo> {
+        let index = self.index();
+        self.files.as_ref().ok().and_then(|files| files.get(index))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ExplorerResponse {
+    Open(PathBuf),
+    OpenMany(Vec<PathBuf>),
+    Directory(PathBuf),
+    Save(PathBuf),
+    /// an operation requested by the user, to be passed to [`Explorer::apply_operation`]
+    Operation(FileOperation),
+    Cancel,
+    Handled,
+    None,
+}
+
+impl Component for Explorer {
+    type Response = ExplorerResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if self.unlisted {
+            self.list();
+        }
+        if let Event::Key(_) = event {
+            self.error = None;
+        }
+        if let Some((confirm, _)) = &mut self.confirm {
+            if let ConfirmResponse::Confirm(yes) = confirm.handle_event(event) {
+                let (_, pending) = self.confirm.take().unwrap();
+                if yes {
+                    return match pending {
+                        PendingConfirm::Overwrite(path) => ExplorerResponse::Save(path),
+                        PendingConfirm::Operation(op) => ExplorerResponse::Operation(op),
+                    };
+                }
+            }
+            ExplorerResponse::Handled
+            // if let Event::Key(key_event) = event {
+            //     match key_event.code {
+            //         KeyCode::Right if *overwrite => {
+            //             *overwrite = false;
+            //             ExplorerResponse::Handled
+            //         }
+            //         KeyCode::Left if !*overwrite => {
+            //             *overwrite = true;
+            //             ExplorerResponse::Handled
+            //         }
+            //         KeyCode::Backspace | KeyCode::Esc => {
+            //             self.confirm_overwrite = None;
+            //             ExplorerResponse::Handled
+            //         }
+            //         KeyCode::Enter => {
+            //             if *overwrite {
+            //                 let p = path.take().unwrap();
+            //                 self.confirm_overwrite = None;
+            //                 ExplorerResponse::Save(p)
+            //             } else {
+            //                 self.confirm_overwrite = None;
+            //                 ExplorerResponse::Handled
+            //             }
+            //         }
+            //         _ => ExplorerResponse::None,
+            //     }
+            // } else {
+            //     ExplorerResponse::None
+            // }
+        } else if self.prompt.is_some() {
+            self.handle_prompt(event)
+        } else if self.places.is_some() {
+            self.handle_places(event)
+        } else if self.breadcrumb.focused || matches!(event, Event::Mouse(_)) {
+            self.handle_breadcrumb(event)
+        } else if self.input_active {
+            match self.input.handle_event(event) {
+                InputResponse::Submit if is_path(&self.input.value) => self.submit_path(),
+                InputResponse::Submit => {
+                    if let Ok(files) = &self.files {
+                        match self.mode {
+                            ExplorerMode::Open | ExplorerMode::SelectDirectory => {
+                                self.input_active = false;
+                                ExplorerResponse::Handled
+                            }
+                            ExplorerMode::Save => {
+                                // behavior:
+                                // if input matches folder name exactly, traverse it
+                                // else save file
+                                let possible_folder = files
+                                    .iter()
+                                    .find(|f| name_of(f) == self.input.value)
+                                    .and_then(|f| {
+                                        if f.meta.resolves_to_dir() {
+                                            Some(f)
+                                        } else {
+                                            None
+                                        }
+                                    });
+                                match possible_folder {
+                                    Some(folder) => {
+                                        let p = folder.path.clone();
+                                        self.set_path(p);
+                                        ExplorerResponse::Handled
+                                    }
+                                    None => {
+                                        let new_path = self.path.join(&self.input.value);
+                                        if self
+                                            .fs
+                                            .metadata(&new_path)
+                                            .is_ok_and(|meta| !meta.is_dir())
+                                        {
+                                            self.confirm = Some((
+                                                Confirm::new("Overwrite file?"),
+                                                PendingConfirm::Overwrite(new_path),
+                                            ));
+                                            ExplorerResponse::Handled
+                                        } else {
+                                            ExplorerResponse::Save(new_path)
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        self.input_active = false;
+                        ExplorerResponse::Handled
+                    }
+                }
+                InputResponse::Invalid => ExplorerResponse::Handled,
+                InputResponse::Cancel => {
+                    self.input_active = false;
+                    if self.fuzzy {
+                        self.input.value.clear();
+                        self.update_files();
+                    }
+                    ExplorerResponse::Handled
+                }
+                InputResponse::Edited { deletion } => {
+                    self.update_files();
+                    if self.fuzzy {
+                        // the best match is listed first
+                        self.table_state.select(Some(0));
+                    } else if !deletion {
+                        // change index to first match
+                        if let Some(index) = self.highlights.iter().position(|h| !h.is_empty()) {
+                            self.table_state.select(Some(index));
+                        }
+                    }
+                    ExplorerResponse::Handled
+                }
+                InputResponse::None => ExplorerResponse::None,
+            }
+        } else if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Esc if self.loader.is_some() => {
+                    self.cancel_loading();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Esc => ExplorerResponse::Cancel,
+                KeyCode::F(5) => {
+                    self.refresh();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::F(7) if self.operations => {
+                    self.open_prompt(NamePrompt::Directory);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('n')
+                    if self.operations && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.open_prompt(NamePrompt::File);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::F(2) if self.operations => {
+                    if let Some(entry) = self.selected_path() {
+                        let path = entry.path.clone();
+                        self.open_prompt(NamePrompt::Rename(path));
+                    }
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Delete if self.operations => {
+                    if let Some(entry) = self.selected_path() {
+                        let name = name_of(entry);
+                        let op = FileOperation::Delete(entry.path.clone());
+                        self.confirm = Some((
+                            Confirm::new(format!("Delete {}?", name)),
+                            PendingConfirm::Operation(op),
+                        ));
+                    }
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char(c @ ('x' | 'c'))
+                    if self.operations && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    if let Some(entry) = self.selected_path() {
+                        self.cut_buffer = Some((entry.path.clone(), c == 'x'));
+                    }
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('v')
+                    if self.operations && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.paste()
+                }
+                KeyCode::Left if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                    self.go_back();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Right if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                    self.go_forward();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Right if self.tree => {
+                    self.expand();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Left if self.tree => {
+                    self.collapse();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('t') => {
+                    self.toggle_tree();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Up | KeyCode::Down
+                    if self.multi_selecting()
+                        && key_event.modifiers.contains(KeyModifiers::SHIFT) =>
+                {
+                    self.extend_selection(key_event.code == KeyCode::Down);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Up => {
+                    self.anchor = None;
+                    self.decrement();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Down => {
+                    self.anchor = None;
+                    self.increment();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char(' ') if self.multi_selecting() => {
+                    self.anchor = Some(self.index());
+                    self.toggle_selected();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('a') if self.multi_selecting() => {
+                    self.toggle_all();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Enter => {
+                    let info = self
+                        .selected_path()
+                        .map(|entry| (entry.path.clone(), entry.meta.resolves_to_dir()));
+                    if let Some((path, is_dir)) = info {
+                        if is_dir {
+                            self.set_path(path);
+                        } else {
+                            match self.mode {
+                                ExplorerMode::Open if self.multi_select => {
+                                    // without a selection, the highlighted file is opened
+                                    let mut paths = std::mem::take(&mut self.selection);
+                                    if paths.is_empty() {
+                                        paths.push(path);
+                                    }
+                                    return ExplorerResponse::OpenMany(paths);
+                                }
+                                ExplorerMode::Open => return ExplorerResponse::Open(path),
+                                ExplorerMode::Save => {
+                                    self.confirm = Some((
+                                        Confirm::new("Overwrite file?"),
+                                        PendingConfirm::Overwrite(path),
+                                    ));
+                                    return ExplorerResponse::Handled;
+                                }
+                                // files aren't listed
+                                ExplorerMode::SelectDirectory => {}
+                            }
+                        }
+                    }
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('c')
+                    if self.mode == ExplorerMode::SelectDirectory
+                        && !key_event
+                            .modifiers
+                            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    match self.selected_path() {
+                        Some(entry) => ExplorerResponse::Directory(entry.path.clone()),
+                        None => ExplorerResponse::Handled,
+                    }
+                }
+                KeyCode::Char('C')
+                    if self.mode == ExplorerMode::SelectDirectory
+                        && !key_event
+                            .modifiers
+                            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    ExplorerResponse::Directory(self.path.clone())
+                }
+                KeyCode::Backspace => {
+                    let parent = self.fs.parent(&self.path);
+                    if let Some(par) = parent {
+                        self.set_path(par);
+                    }
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('/') => {
+                    self.input_active = true;
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('g') => {
+                    self.breadcrumb.focused = true;
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('b') => {
+                    self.open_places();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('B') => {
+                    self.toggle_bookmark();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char(c @ '1'..='9') => {
+                    self.jump_to_bookmark(c);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('n') => {
+                    self.cycle_match(true);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('N') => {
+                    self.cycle_match(false);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('p') => {
+                    self.preview = !self.preview;
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('.') => {
+                    self.show_hidden = !self.show_hidden;
+                    self.update_files();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('s') => {
+                    self.sort.key = self.sort.key.next();
+                    self.update_files();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('r') => {
+                    self.sort.ascending = !self.sort.ascending;
+                    self.update_files();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Tab => {
+                    self.cycle_filter(true);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::BackTab => {
+                    self.cycle_filter(false);
+                    ExplorerResponse::Handled
+                }
+                _ => ExplorerResponse::None,
+            }
+        } else {
+            ExplorerResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
+        if self.unlisted {
+            self.list();
+        }
+        self.input.focused = self.input_active;
+        let title = match self.mode {
+            ExplorerMode::Open if !self.selection.is_empty() => {
+                format!("Open File ({} selected)", self.selection.len())
+            }
+            ExplorerMode::Open => String::from("Open File"),
+            ExplorerMode::Save => String::from("Save File"),
+            ExplorerMode::SelectDirectory => String::from("Select Directory"),
+        };
+        let outer = Block::default()
+            .title(Span::styled(title, Style::default().fg(Color::White)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+        let inner = outer.inner(rect);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Percentage(100),
+            ])
+            .split(inner);
+
+        self.breadcrumb.set_path(&self.path);
+
+        Widget::render(outer, rect, buf);
+        let mut labels = Vec::new();
+        if let Some(loader) = &self.loader {
+            labels.push(Span::styled(
+                format!(" Loading… {} entries", loader.loaded()),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        if !self.filters.is_empty() {
+            let label = match self.active_filter() {
+                Some(filter) => format!(" [{}]", filter.name()),
+                None => String::from(" [All files]"),
+            };
+            labels.push(Span::styled(label, Style::default().fg(Color::Cyan)));
+        }
+        if labels.is_empty() {
+            self.breadcrumb.draw(areas[0], buf);
+        } else {
+            let labels = Spans::from(labels);
+            let header = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(labels.width() as u16),
+                ])
+                .split(areas[0]);
+            let labels = Paragraph::new(labels).alignment(Alignment::Right);
+            self.breadcrumb.draw(header[0], buf);
+            Widget::render(labels, header[1], buf);
+        }
+        match &self.error {
+            Some(e) if !self.input_active => {
+                let p = Paragraph::new(Span::styled(e.clone(), Style::default().fg(Color::Red)));
+                Widget::render(p, areas[1], buf);
+            }
+            _ => self.input.draw(areas[1], buf),
+        }
+        let list_area = if self.preview {
+            let split = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(areas[2]);
+            self.draw_preview(split[1], buf);
+            split[0]
+        } else {
+            areas[2]
+        };
+        match &self.files {
+            Ok(files) => {
+                let names = files
+                    .iter()
+                    .zip(&self.highlights)
+                    .enumerate()
+                    .map(|(i, (p, highlight))| {
+                        let name = name_of(p);
+                        let string = if p.meta.resolves_to_dir() {
+                            format!("{} /", name)
+                        } else {
+                            name
+                        };
+                        let chosen = self.selection.contains(&p.path);
+                        let cells = self.columns.iter().map(|column| match column {
+                            ExplorerColumn::Name => {
+                                let mut spans = highlight_spans(&string, highlight);
+                                if self.tree {
+                                    let marker = if !p.meta.resolves_to_dir() {
+                                        "  "
+                                    } else if self.expanded.contains_key(&p.path) {
+                                        "▾ "
+                                    } else {
+                                        "▸ "
+                                    };
+                                    spans.0.insert(0, Span::raw(marker));
+                                    if let Some(guide) = self.guides.get(i) {
+                                        spans.0.insert(
+                                            0,
+                                            Span::styled(
+                                                guide.clone(),
+                                                Style::default().fg(Color::DarkGray),
+                                            ),
+                                        );
+                                    }
+                                }
+                                if self.multi_selecting() {
+                                    let mark = if chosen { "● " } else { "  " };
+                                    spans.0.insert(0, Span::raw(mark));
+                                }
+                                spans.0.extend(entry_note(&p.meta));
+                                Cell::from(spans)
+                            }
+                            _ => Cell::from(column.value(p)),
+                        });
+                        let row = Row::new(cells.collect::<Vec<_>>());
+                        if chosen {
+                            row.style(Style::default().fg(Color::Cyan))
+                        } else if p.meta.error.is_some() || p.meta.is_broken_link() {
+                            row.style(Style::default().fg(Color::Red))
+                        } else {
+                            row
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let fixed = self
+                    .columns
+                    .iter()
+                    .map(|column| column.width().map_or(0, |w| w + COLUMN_SPACING))
+                    .sum::<u16>();
+                let widths = self
+                    .columns
+                    .iter()
+                    .map(|column| {
+                        Constraint::Length(
+                            column
+                                .width()
+                                .unwrap_or_else(|| list_area.width.saturating_sub(fixed)),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let mut table = Table::new(names)
+                    .widths(&widths)
+                    .column_spacing(COLUMN_SPACING)
+                    .highlight_style(Style::default().bg(Color::Green));
+                if self.columns.len() > 1 {
+                    let arrow = if self.sort.ascending { "▲" } else { "▼" };
+                    let titles = self.columns.iter().map(|column| {
+                        if *column == self.sort.key.column() {
+                            format!("{} {}", column.title(), arrow)
+                        } else {
+                            column.title().to_string()
+                        }
+                    });
+                    table = table.header(
+                        Row::new(titles.collect::<Vec<_>>())
+                            .style(Style::default().fg(Color::Yellow)),
+                    );
+                }
+                StatefulWidget::render(table, list_area, buf, &mut self.table_state);
+            }
+            Err(e) => {
+                let p = Paragraph::new(Span::styled(e, Style::default().fg(Color::Red)));
+                Widget::render(p, list_area, buf);
+            }
+        }
+        // the completions drop down over the listing
+        if self.error.is_none() || self.input_active {
+            self.input.draw_completions(areas[1], buf);
+        }
+        self.draw_places(rect, buf);
+        // overwrite box appears above everything
+        if let Some((prompt, input)) = &mut self.prompt {
+            let block = Block::default()
+                .title(Span::styled(
+                    prompt.title(),
+                    Style::default().fg(Color::White),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow));
+            let area = rect.centered(Rect {
+                x: 0,
+                y: 0,
+                width: 40,
+                height: 3,
+            });
+            let inner = block.inner(area);
+            Widget::render(Clear, area, buf);
+            Widget::render(block, area, buf);
+            input.draw(inner, buf);
+        }
+        if let Some((confirm, _)) = &mut self.confirm {
+            confirm.draw(rect, buf)
+        }
+    }
+}
+
+/// Describes a link's target or an unreadable entry, after its name
+fn entry_note<'b>(meta: &EntryMeta) -> Vec<Span<'b>> {
+    let style = Style::default().fg(Color::DarkGray);
+    if let Some(e) = &meta.error {
+        vec![Span::styled(format!(" ⚠ {}", e), style)]
+    } else if let Some(link) = &meta.link {
+        let mut note = vec![Span::styled(
+            format!(" -> {}", link.target.display()),
+            style,
+        )];
+        if meta.is_broken_link() {
+            note.push(Span::styled(" (broken)", Style::default().fg(Color::Red)));
+        }
+        note
+    } else {
+        Vec::new()
+    }
+}
+
+/// Splits a name into spans, styling the chars at the matched indices
+fn highlight_spans<'b>(name: &str, highlight: &[usize]) -> Spans<'b> {
+    let style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in name.chars().enumerate() {
+        let matched = highlight.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            let text = std::mem::take(&mut run);
+            spans.push(if run_matched {
+                Span::styled(text, style)
+            } else {
+                Span::raw(text)
+            });
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    spans.push(if run_matched {
+        Span::styled(run, style)
+    } else {
+        Span::raw(run)
+    });
+    Spans::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn extends_selection_in_an_empty_directory() {
+        let fs = MemoryFileSystem::new().directory("/empty");
+        let mut explorer =
+            Explorer::with_file_system("/empty", ExplorerMode::Open, fs).multi_select(true);
+        explorer.handle_event(key(KeyCode::Down, KeyModifiers::SHIFT));
+        assert!(explorer.selection().is_empty());
+    }
+
+    #[test]
+    fn extends_selection_after_the_list_shrinks() {
+        let mut fs = MemoryFileSystem::new();
+        for name in [".a", ".b", ".c", ".d", ".e", ".f", "g", "h"] {
+            fs = fs.file(Path::new("/dir").join(name), "");
+        }
+        let mut explorer =
+            Explorer::with_file_system("/dir", ExplorerMode::Open, fs).multi_select(true);
+        for _ in 0..5 {
+            explorer.handle_event(key(KeyCode::Down, KeyModifiers::NONE));
+        }
+        explorer.handle_event(key(KeyCode::Char(' '), KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Char('.'), KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Up, KeyModifiers::SHIFT));
+        assert_eq!(explorer.selection().len(), 3);
+    }
+
+    fn sample() -> Explorer {
+        let fs = MemoryFileSystem::new()
+            .file("/home/a/1.txt", "")
+            .file("/home/a/2.txt", "")
+            .file("/home/a/3.txt", "")
+            .directory("/home/b")
+            .file("/home/c/x.txt", "");
+        Explorer::with_file_system("/home", ExplorerMode::Open, fs)
+    }
+
+    fn highlighted(explorer: &Explorer) -> Option<&Path> {
+        explorer.selected_path().map(|entry| entry.path.as_path())
+    }
+
+    #[test]
+    fn navigates_into_directories_and_back() {
+        let mut explorer = sample();
+        explorer.handle_event(key(KeyCode::Down, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(explorer.path, Path::new("/home/b"));
+        explorer.handle_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(explorer.path, Path::new("/home"));
+        // the directory just left is highlighted
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/b")));
+        explorer.handle_event(key(KeyCode::Left, KeyModifiers::ALT));
+        assert_eq!(explorer.path, Path::new("/home/b"));
+        explorer.handle_event(key(KeyCode::Right, KeyModifiers::ALT));
+        assert_eq!(explorer.path, Path::new("/home"));
+    }
+
+    #[test]
+    fn remembers_the_highlighted_entry_of_each_directory() {
+        let mut explorer = sample();
+        explorer.handle_event(key(KeyCode::Enter, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/a/2.txt")));
+        explorer.handle_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Down, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Down, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(explorer.path, Path::new("/home/c"));
+        explorer.handle_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/c")));
+        explorer.handle_event(key(KeyCode::Up, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Up, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/a/2.txt")));
+    }
+
+    #[test]
+    fn is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Explorer>();
+    }
+
+    #[test]
+    fn lists_the_first_directory_in_the_background() {
+        let mut explorer = sample().background_loading(true);
+        assert!(explorer.loader.is_none());
+        explorer.poll();
+        while explorer.is_loading() {
+            explorer.poll();
+        }
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/a")));
+    }
+
+    #[test]
+    fn keeps_the_selection_through_a_background_refresh() {
+        let mut explorer = sample().background_loading(true);
+        let finish = |explorer: &mut Explorer| {
+            explorer.poll();
+            while explorer.is_loading() {
+                explorer.poll();
+            }
+        };
+        finish(&mut explorer);
+        // the highlighted entry moves down a row
+        explorer.fs.create_dir(Path::new("/home/0")).unwrap();
+        explorer.refresh();
+        finish(&mut explorer);
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/a")));
+    }
+
+    #[test]
+    fn draws_completions_over_the_listing() {
+        let mut explorer = sample();
+        explorer.handle_event(key(KeyCode::Char('/'), KeyModifiers::NONE));
+        for c in "./".chars() {
+            explorer.handle_event(key(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        let area = Rect::new(0, 0, 40, 12);
+        let mut buf = Buffer::empty(area);
+        explorer.draw(area, &mut buf);
+        let rows = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf.get(x, y).symbol.clone())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert!(rows.iter().any(|row| row.contains(" ./a/ ")), "{:#?}", rows);
+    }
+
+    #[test]
+    fn names_entries_in_the_highlighted_directory() {
+        let mut explorer = sample().tree(true).file_operations(true);
+        let submit = |explorer: &mut Explorer, name: &str| {
+            for _ in 0..10 {
+                explorer.handle_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+            }
+            for c in name.chars() {
+                explorer.handle_event(key(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+            explorer.handle_event(key(KeyCode::Enter, KeyModifiers::NONE))
+        };
+        explorer.handle_event(key(KeyCode::Right, KeyModifiers::NONE));
+        explorer.handle_event(key(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(highlighted(&explorer), Some(Path::new("/home/a/1.txt")));
+
+        explorer.handle_event(key(KeyCode::F(2), KeyModifiers::NONE));
+        let response = submit(&mut explorer, "z.txt");
+        assert!(matches!(
+            response,
+            ExplorerResponse::Operation(FileOperation::Rename { to, .. })
+                if to == Path::new("/home/a/z.txt")
+        ));
+
+        explorer.handle_event(key(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        let response = submit(&mut explorer, "new.txt");
+        assert!(matches!(
+            response,
+            ExplorerResponse::Operation(FileOperation::CreateFile(path))
+                if path == Path::new("/home/a/new.txt")
+        ));
+    }
+
+    #[test]
+    fn chooses_directories_only_with_plain_keys() {
+        let fs = MemoryFileSystem::new().directory("/a/b");
+        let mut explorer = Explorer::with_file_system("/a", ExplorerMode::SelectDirectory, fs);
+        for modifiers in [KeyModifiers::CONTROL, KeyModifiers::ALT] {
+            for c in ['c', 'C'] {
+                let response = explorer.handle_event(key(KeyCode::Char(c), modifiers));
+                assert!(!matches!(response, ExplorerResponse::Directory(_)));
+            }
+        }
+        let response = explorer.handle_event(key(KeyCode::Char('c'), KeyModifiers::NONE));
+        assert!(matches!(response, ExplorerResponse::Directory(path) if path == Path::new("/a/b")));
+    }
+}
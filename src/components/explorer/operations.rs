@@ -0,0 +1,88 @@
+use super::FileSystem;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A change to the file system requested through an [`Explorer`](crate::components::Explorer).
+///
+/// It isn't performed until passed to
+/// [`Explorer::apply_operation`](crate::components::Explorer::apply_operation)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOperation {
+    CreateDirectory(PathBuf),
+    CreateFile(PathBuf),
+    Rename { from: PathBuf, to: PathBuf },
+    Delete(PathBuf),
+    Copy { from: PathBuf, to: PathBuf },
+    Move { from: PathBuf, to: PathBuf },
+}
+
+impl FileOperation {
+    /// The path selected once the operation is done
+    pub fn target(&self) -> Option<&Path> {
+        match self {
+            FileOperation::CreateDirectory(path) | FileOperation::CreateFile(path) => Some(path),
+            FileOperation::Rename { to, .. }
+            | FileOperation::Copy { to, .. }
+            | FileOperation::Move { to, .. } => Some(to),
+            FileOperation::Delete(_) => None,
+        }
+    }
+
+    pub(super) fn apply(&self, fs: &dyn FileSystem) -> io::Result<()> {
+        match self {
+            FileOperation::CreateDirectory(path) => fs.create_dir(path),
+            FileOperation::CreateFile(path) => fs.create_file(path),
+            FileOperation::Rename { from, to } | FileOperation::Move { from, to } => {
+                check_target(fs, from, to)?;
+                fs.rename(from, to)
+            }
+            FileOperation::Delete(path) => fs.remove(path),
+            FileOperation::Copy { from, to } => {
+                check_target(fs, from, to)?;
+                fs.copy(from, to)
+            }
+        }
+    }
+}
+
+fn check_target(fs: &dyn FileSystem, from: &Path, to: &Path) -> io::Result<()> {
+    if to.starts_with(from) {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot move or copy a directory into itself",
+        ))
+    } else if fs.symlink_metadata(to).is_ok() {
+        // a dangling link doesn't exist when followed, but would be written through
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::explorer::file_system::tests::scratch_dir;
+    use crate::components::StdFileSystem;
+    use std::fs;
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_to_write_through_dangling_links() {
+        let dir = scratch_dir("dangling-link");
+        fs::write(dir.join("file.txt"), "1").unwrap();
+        std::os::unix::fs::symlink(dir.join("target.txt"), dir.join("link")).unwrap();
+        let error = check_target(&StdFileSystem, &dir.join("file.txt"), &dir.join("link"));
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        let copy = FileOperation::Copy {
+            from: dir.join("file.txt"),
+            to: dir.join("link"),
+        };
+        assert!(copy.apply(&StdFileSystem).is_err());
+        assert!(!dir.join("target.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
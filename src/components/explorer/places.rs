@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use std::fs::{read_to_string, write};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use tui::style::Color;
+
+/// How many recently visited directories are remembered
+pub(super) const RECENT_CAPACITY: usize = 10;
+
+/// File systems listed in `/proc/mounts` that aren't worth jumping to
+const PSEUDO_FILE_SYSTEMS: [&str; 16] = [
+    "proc",
+    "sysfs",
+    "devpts",
+    "devtmpfs",
+    "cgroup",
+    "cgroup2",
+    "securityfs",
+    "pstore",
+    "debugfs",
+    "tracefs",
+    "mqueue",
+    "hugetlbfs",
+    "bpf",
+    "configfs",
+    "fusectl",
+    "autofs",
+];
+
+/// Mount points under these are the system's own, rather than volumes
+const SYSTEM_DIRS: [&str; 4] = ["/proc", "/sys", "/dev", "/run"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PlaceKind {
+    Bookmark,
+    Recent,
+    Home,
+    Root,
+    Mount,
+}
+
+impl PlaceKind {
+    pub(super) fn label(&self) -> &'static str {
+        match self {
+            PlaceKind::Bookmark => "bookmark",
+            PlaceKind::Recent => "recent",
+            PlaceKind::Home => "home",
+            PlaceKind::Root => "root",
+            PlaceKind::Mount => "mount",
+        }
+    }
+
+    pub(super) fn color(&self) -> Color {
+        match self {
+            PlaceKind::Bookmark => Color::Yellow,
+            PlaceKind::Recent => Color::Cyan,
+            PlaceKind::Home | PlaceKind::Root => Color::Green,
+            PlaceKind::Mount => Color::Magenta,
+        }
+    }
+}
+
+/// A directory listed in the places popup
+#[derive(Debug, Clone)]
+pub(super) struct Place {
+    pub(super) kind: PlaceKind,
+    pub(super) path: PathBuf,
+}
+
+/// Lists bookmarks, then recent directories, home, root and mount points
+pub(super) fn gather(bookmarks: &[PathBuf], recent: &VecDeque<PathBuf>) -> Vec<Place> {
+    let mut places = Vec::new();
+    let mut add = |kind, path: PathBuf| {
+        // a bookmark is enough, so later duplicates are left out
+        if kind == PlaceKind::Bookmark || !places.iter().any(|p: &Place| p.path == path) {
+            places.push(Place { kind, path });
+        }
+    };
+    for path in bookmarks {
+        add(PlaceKind::Bookmark, path.clone());
+    }
+    for path in recent {
+        add(PlaceKind::Recent, path.clone());
+    }
+    if let Some(home) = home() {
+        add(PlaceKind::Home, home);
+    }
+    add(PlaceKind::Root, root());
+    for mount in mounts() {
+        add(PlaceKind::Mount, mount);
+    }
+    places
+}
+
+/// Reads bookmarks saved with [`save_bookmarks`]. A missing file gives no bookmarks
+pub(super) fn load_bookmarks(path: &Path) -> io::Result<Vec<PathBuf>> {
+    match read_to_string(path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(PathBuf::from)
+            .collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes bookmarks to a file, one per line
+pub(super) fn save_bookmarks(path: &Path, bookmarks: &[PathBuf]) -> io::Result<()> {
+    let mut contents = String::new();
+    for bookmark in bookmarks {
+        contents.push_str(&bookmark.to_string_lossy());
+        contents.push('\n');
+    }
+    write(path, contents)
+}
+
+pub(super) fn home() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+fn root() -> PathBuf {
+    std::env::current_dir()
+        .ok()
+        .and_then(|dir| dir.ancestors().last().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("/"))
+}
+
+/// Mount points of real file systems, from `/proc/mounts` where it exists
+fn mounts() -> Vec<PathBuf> {
+    let contents = match read_to_string("/proc/mounts") {
+        Ok(contents) => contents,
+        Err(_) => return Vec::new(),
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?;
+            let file_system = fields.next()?;
+            let mount_point = unescape_mount(mount_point);
+            let system_dir = SYSTEM_DIRS
+                .iter()
+                .any(|dir| Path::new(&mount_point).starts_with(dir));
+            if PSEUDO_FILE_SYSTEMS.contains(&file_system) || system_dir || mount_point == "/" {
+                return None;
+            }
+            Some(PathBuf::from(mount_point))
+        })
+        .collect()
+}
+
+/// Decodes the octal escapes `/proc/mounts` uses for spaces and other characters
+fn unescape_mount(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)))
+            .map(|digits| {
+                digits
+                    .iter()
+                    .fold(0u8, |n, d| n.wrapping_mul(8) + (d - b'0'))
+            });
+        match octal {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 4;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file_system::tests::scratch_dir;
+    use super::*;
+
+    #[test]
+    fn unescapes_mount_points() {
+        assert_eq!(unescape_mount(r"/mnt/my\040disk"), "/mnt/my disk");
+        assert_eq!(unescape_mount(r"/mnt/tab\011end\134"), "/mnt/tab\tend\\");
+        // anything that isn't three octal digits is kept as it is
+        assert_eq!(unescape_mount(r"/mnt/a\08b\04"), r"/mnt/a\08b\04");
+    }
+
+    #[test]
+    fn saves_and_loads_bookmarks() {
+        let dir = scratch_dir("bookmarks");
+        let file = dir.join("bookmarks");
+        assert!(load_bookmarks(&file).unwrap().is_empty());
+        let bookmarks = vec![PathBuf::from("/home/a b"), PathBuf::from("/tmp")];
+        save_bookmarks(&file, &bookmarks).unwrap();
+        assert_eq!(load_bookmarks(&file).unwrap(), bookmarks);
+        save_bookmarks(&file, &[]).unwrap();
+        assert!(load_bookmarks(&file).unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+use super::columns::{format_permissions, format_size, format_time, natural_cmp};
+use super::FileSystem;
+use std::path::Path;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+
+/// The most lines loaded for a preview
+const PREVIEW_LINES: usize = 200;
+/// The most bytes read from a file for a preview
+const PREVIEW_BYTES: u64 = 16 * 1024;
+/// The bytes shown per line of a hex dump
+const HEX_WIDTH: usize = 16;
+
+/// The contents shown for the highlighted entry of an [`Explorer`](super::Explorer)
+#[derive(Debug, Clone)]
+pub(super) enum Preview {
+    Text(Vec<String>),
+    /// lines of a hex dump, for files that aren't text
+    Binary(Vec<String>),
+    Directory(Vec<String>),
+    Metadata(Vec<String>),
+    Error(String),
+}
+
+impl Preview {
+    pub(super) fn load(fs: &dyn FileSystem, path: &Path) -> Self {
+        // follows links, unlike the metadata of directory entries
+        let meta = match fs.metadata(path) {
+            Ok(meta) => meta,
+            Err(e) => return Preview::Error(format!("{}", e)),
+        };
+        // listing an archive means reading it, which is left until it is opened
+        if meta.is_dir() && !fs.is_archive(path) {
+            Self::load_dir(fs, path)
+        } else if meta.is_file() && meta.len > 0 {
+            Self::load_file(fs, path)
+        } else {
+            let mut lines = vec![
+                format!("Size: {}", format_size(meta.len)),
+                format!("Permissions: {}", format_permissions(&meta)),
+            ];
+            if let Some(time) = meta.modified {
+                lines.push(format!("Modified: {}", format_time(time)));
+            }
+            if meta.is_file() {
+                lines.insert(0, String::from("Empty file"));
+            } else if meta.is_dir() {
+                lines.insert(0, String::from("Archive"));
+            }
+            Preview::Metadata(lines)
+        }
+    }
+
+    fn load_dir(fs: &dyn FileSystem, path: &Path) -> Self {
+        match fs.read_dir(path) {
+            Ok(entries) => {
+                let mut names = entries
+                    .into_iter()
+                    .map(|(path, meta)| {
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        if meta.is_dir() {
+                            format!("{} /", name)
+                        } else {
+                            name
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                names.sort_by(|a, b| natural_cmp(a, b));
+                names.truncate(PREVIEW_LINES);
+                Preview::Directory(names)
+            }
+            Err(e) => Preview::Error(format!("{}", e)),
+        }
+    }
+
+    fn load_file(fs: &dyn FileSystem, path: &Path) -> Self {
+        let bytes = match fs.read(path, PREVIEW_BYTES) {
+            Ok(bytes) => bytes,
+            Err(e) => return Preview::Error(format!("{}", e)),
+        };
+        let text = match std::str::from_utf8(&bytes) {
+            Ok(text) => Some(text),
+            // the read may have split the last character
+            Err(e) if e.error_len().is_none() => {
+                std::str::from_utf8(&bytes[..e.valid_up_to()]).ok()
+            }
+            Err(_) => None,
+        };
+        match text {
+            Some(text) if !text.contains('\0') => Preview::Text(
+                text.lines()
+                    .take(PREVIEW_LINES)
+                    .map(|line| line.replace('\t', "    "))
+                    .collect(),
+            ),
+            _ => Preview::Binary(
+                bytes
+                    .chunks(HEX_WIDTH)
+                    .take(PREVIEW_LINES)
+                    .enumerate()
+                    .map(|(i, chunk)| hex_line(i * HEX_WIDTH, chunk))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub(super) fn lines<'b>(&self) -> Vec<Spans<'b>> {
+        let styled = |lines: &[String], style: Style| {
+            lines
+                .iter()
+                .map(|line| Spans::from(Span::styled(line.clone(), style)))
+                .collect()
+        };
+        match self {
+            Preview::Text(lines) => styled(lines, Style::default()),
+            Preview::Binary(lines) => styled(lines, Style::default().fg(Color::Magenta)),
+            Preview::Directory(lines) if lines.is_empty() => styled(
+                &[String::from("Empty directory")],
+                Style::default().fg(Color::Gray),
+            ),
+            Preview::Directory(lines) => styled(lines, Style::default().fg(Color::Blue)),
+            Preview::Metadata(lines) => styled(lines, Style::default().fg(Color::Gray)),
+            Preview::Error(e) => styled(std::slice::from_ref(e), Style::default().fg(Color::Red)),
+        }
+    }
+}
+
+fn hex_line(offset: usize, chunk: &[u8]) -> String {
+    let hex = chunk
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let ascii = chunk
+        .iter()
+        .map(|b| {
+            if b.is_ascii_graphic() || *b == b' ' {
+                *b as char
+            } else {
+                '.'
+            }
+        })
+        .collect::<String>();
+    format!(
+        "{:08x}  {:<width$}  {}",
+        offset,
+        hex,
+        ascii,
+        width = HEX_WIDTH * 3 - 1
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::MemoryFileSystem;
+
+    fn preview(contents: Vec<u8>) -> Preview {
+        let fs = MemoryFileSystem::new().file("/file", contents);
+        Preview::load(&fs, Path::new("/file"))
+    }
+
+    #[test]
+    fn keeps_text_split_at_the_read_limit() {
+        let mut contents = vec![b'a'; PREVIEW_BYTES as usize - 1];
+        contents.extend("é".as_bytes());
+        match preview(contents) {
+            Preview::Text(lines) => assert_eq!(lines[0].len(), PREVIEW_BYTES as usize - 1),
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn detects_binary_files() {
+        assert!(matches!(preview(b"a\0b".to_vec()), Preview::Binary(_)));
+        // invalid in the middle, rather than cut off at the end
+        assert!(matches!(preview(b"a\xffb".to_vec()), Preview::Binary(_)));
+        assert!(matches!(preview(b"a\tb".to_vec()), Preview::Text(lines) if lines == ["a    b"]));
+    }
+
+    #[test]
+    fn formats_hex_lines() {
+        assert_eq!(
+            hex_line(0x20, b"Hi\n\xff"),
+            format!("00000020  48 69 0a ff{}  Hi..", " ".repeat(36))
+        );
+        let full = hex_line(0, &[b'a'; HEX_WIDTH]);
+        assert_eq!(full.len(), 8 + 2 + HEX_WIDTH * 3 - 1 + 2 + HEX_WIDTH);
+    }
+}
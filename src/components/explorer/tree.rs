@@ -0,0 +1,59 @@
+use super::EntryInfo;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Lists the entries with the children of expanded directories after each, returning the
+/// indentation guides drawn before each name. `prepare` filters and sorts each directory
+pub(super) fn flatten(
+    entries: Vec<EntryInfo>,
+    expanded: &HashMap<PathBuf, Vec<EntryInfo>>,
+    prepare: &dyn Fn(Vec<EntryInfo>) -> Vec<EntryInfo>,
+) -> (Vec<EntryInfo>, Vec<String>) {
+    let mut files = Vec::new();
+    let mut guides = Vec::new();
+    push_level(
+        prepare(entries),
+        None,
+        expanded,
+        prepare,
+        &mut files,
+        &mut guides,
+    );
+    (files, guides)
+}
+
+/// Adds one directory's entries, with `prefix` being the guides of its ancestors.
+/// The top level has no guides at all
+fn push_level(
+    entries: Vec<EntryInfo>,
+    prefix: Option<&str>,
+    expanded: &HashMap<PathBuf, Vec<EntryInfo>>,
+    prepare: &dyn Fn(Vec<EntryInfo>) -> Vec<EntryInfo>,
+    files: &mut Vec<EntryInfo>,
+    guides: &mut Vec<String>,
+) {
+    let count = entries.len();
+    for (i, entry) in entries.into_iter().enumerate() {
+        let last = i + 1 == count;
+        let (guide, child_prefix) = match prefix {
+            Some(prefix) => (
+                format!("{}{}", prefix, if last { "└─ " } else { "├─ " }),
+                format!("{}{}", prefix, if last { "   " } else { "│  " }),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let children = expanded.get(&entry.path).cloned();
+        files.push(entry);
+        guides.push(guide);
+        if let Some(children) = children {
+            push_level(
+                prepare(children),
+                Some(&child_prefix),
+                expanded,
+                prepare,
+                files,
+                guides,
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use super::FileSystem;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often a notify watcher checks for changes when notifications are unavailable
+#[cfg(feature = "watch")]
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How an [`Explorer`](crate::components::Explorer) notices changes to the listed directory.
+///
+/// Changes are picked up by [`Explorer::poll`](crate::components::Explorer::poll)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatchMode {
+    /// only refreshing with F5
+    #[default]
+    Off,
+    /// checking the directory at an interval
+    Poll(Duration),
+    /// using the platform's file notifications, or polling if they're unavailable
+    #[cfg(feature = "watch")]
+    Notify,
+}
+
+/// What a directory looked like when last checked
+#[derive(Debug, Clone, PartialEq)]
+enum Stamp {
+    Modified(SystemTime),
+    /// for file systems without modification times
+    Entries(Vec<PathBuf>),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Watcher {
+    mode: WatchMode,
+    last_check: Instant,
+    stamp: Option<Stamp>,
+    #[cfg(feature = "watch")]
+    notifier: Option<std::sync::Arc<notifier::Notifier>>,
+}
+
+impl Watcher {
+    pub(super) fn new(mode: WatchMode) -> Self {
+        Self {
+            mode,
+            last_check: Instant::now(),
+            stamp: None,
+            #[cfg(feature = "watch")]
+            notifier: match mode {
+                WatchMode::Notify => notifier::Notifier::new().map(std::sync::Arc::new),
+                _ => None,
+            },
+        }
+    }
+
+    /// Starts watching a directory, treating its current state as seen
+    pub(super) fn watch(&mut self, fs: &dyn FileSystem, path: &Path) {
+        self.last_check = Instant::now();
+        #[cfg(feature = "watch")]
+        if let Some(notifier) = &self.notifier {
+            if notifier.watch(path) {
+                return;
+            }
+        }
+        self.stamp = match self.mode {
+            WatchMode::Off => None,
+            _ => stamp(fs, path),
+        };
+    }
+
+    /// Whether the directory has changed since it was last seen
+    pub(super) fn changed(&mut self, fs: &dyn FileSystem, path: &Path) -> bool {
+        let interval = match self.mode {
+            WatchMode::Off => return false,
+            WatchMode::Poll(interval) => interval,
+            #[cfg(feature = "watch")]
+            WatchMode::Notify => {
+                if let Some(notifier) = &self.notifier {
+                    if notifier.is_watching(path) {
+                        return notifier.changed();
+                    }
+                }
+                DEFAULT_INTERVAL
+            }
+        };
+        if self.last_check.elapsed() < interval {
+            return false;
+        }
+        self.last_check = Instant::now();
+        let current = stamp(fs, path);
+        if current == self.stamp {
+            false
+        } else {
+            self.stamp = current;
+            true
+        }
+    }
+}
+
+fn stamp(fs: &dyn FileSystem, path: &Path) -> Option<Stamp> {
+    match fs.metadata(path).ok()?.modified {
+        Some(modified) => Some(Stamp::Modified(modified)),
+        None => {
+            let mut paths = fs
+                .read_dir(path)
+                .ok()?
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>();
+            paths.sort();
+            Some(Stamp::Entries(paths))
+        }
+    }
+}
+
+#[cfg(feature = "watch")]
+mod notifier {
+    use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+    use std::fmt;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{channel, Receiver};
+    use std::sync::Mutex;
+
+    /// Receives the platform's notifications for one directory at a time
+    pub(super) struct Notifier {
+        watcher: Mutex<RecommendedWatcher>,
+        events: Mutex<Receiver<notify::Result<notify::Event>>>,
+        watched: Mutex<Option<PathBuf>>,
+    }
+
+    impl fmt::Debug for Notifier {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Notifier")
+                .field("watched", &self.watched)
+                .finish()
+        }
+    }
+
+    impl Notifier {
+        pub(super) fn new() -> Option<Self> {
+            let (sender, events) = channel();
+            let watcher = notify::recommended_watcher(sender).ok()?;
+            Some(Self {
+                watcher: Mutex::new(watcher),
+                events: Mutex::new(events),
+                watched: Mutex::new(None),
+            })
+        }
+
+        /// Moves the watch to a directory, returning whether it succeeded
+        pub(super) fn watch(&self, path: &Path) -> bool {
+            let mut watcher = self.watcher.lock().unwrap();
+            let mut watched = self.watched.lock().unwrap();
+            if let Some(old) = watched.take() {
+                let _ = watcher.unwatch(&old);
+            }
+            // events from the old directory no longer matter
+            while self.events.lock().unwrap().try_recv().is_ok() {}
+            if watcher.watch(path, RecursiveMode::NonRecursive).is_ok() {
+                *watched = Some(path.to_path_buf());
+                true
+            } else {
+                false
+            }
+        }
+
+        pub(super) fn is_watching(&self, path: &Path) -> bool {
+            self.watched.lock().unwrap().as_deref() == Some(path)
+        }
+
+        /// Whether any notification arrived since the last call
+        pub(super) fn changed(&self) -> bool {
+            let events = self.events.lock().unwrap();
+            let mut changed = false;
+            while let Ok(event) = events.try_recv() {
+                changed |= event.is_ok_and(|event| !event.kind.is_access());
+            }
+            changed
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+use std::fs::{read_to_string, write};
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// How many entries a default history keeps
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+/// Previously submitted values of an [`Input`](crate::components::Input), oldest first
+#[derive(Debug, Clone)]
+pub struct History {
+    entries: VecDeque<String>,
+    capacity: usize,
+    /// the recalled entry, while browsing
+    position: Option<usize>,
+    /// the value typed before browsing, used to filter entries
+    draft: String,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Loads a history saved with [`History::save`]. A missing file gives an empty history
+    pub fn load<P: AsRef<Path>>(path: P, capacity: usize) -> io::Result<Self> {
+        let mut history = Self::new(capacity);
+        match read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .for_each(|line| history.push(line.to_string())),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(history)
+    }
+
+    /// Writes the entries to a file, one per line
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut contents = String::new();
+        for entry in &self.entries {
+            contents.push_str(entry);
+            contents.push('\n');
+        }
+        write(path, contents)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter()
+    }
+
+    /// Records an entry, skipping blanks and repeats of the latest entry
+    pub fn push(&mut self, entry: String) {
+        self.reset();
+        if entry.trim().is_empty() || entry.contains('\n') || self.entries.back() == Some(&entry) {
+            return;
+        }
+        self.entries.push_back(entry);
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.reset();
+    }
+
+    /// Stops browsing, so the next recall filters by the new value
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+
+    /// Recalls an older entry starting with the value typed before browsing
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        self.recall_older(current, |entry, draft| entry.starts_with(draft))
+    }
+
+    /// Recalls an older entry containing the value typed before browsing
+    pub fn search(&mut self, current: &str) -> Option<&str> {
+        self.recall_older(current, |entry, draft| entry.contains(draft))
+    }
+
+    /// Recalls a newer entry starting with the typed value.
+    ///
+    /// Moving past the newest entry stops browsing and returns the typed value
+    pub fn newer(&mut self) -> Option<String> {
+        let position = self.position?;
+        let draft = &self.draft;
+        match (position + 1..self.entries.len()).find(|i| self.entries[*i].starts_with(draft)) {
+            Some(index) => {
+                self.position = Some(index);
+                Some(self.entries[index].clone())
+            }
+            None => {
+                let draft = self.draft.clone();
+                self.reset();
+                Some(draft)
+            }
+        }
+    }
+
+    fn recall_older<F: Fn(&str, &str) -> bool>(
+        &mut self,
+        current: &str,
+        matches: F,
+    ) -> Option<&str> {
+        let end = match self.position {
+            Some(position) => position,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len()
+            }
+        };
+        let draft = &self.draft;
+        let index = (0..end)
+            .rev()
+            .find(|i| self.entries[*i] != current && matches(&self.entries[*i], draft))?;
+        self.position = Some(index);
+        Some(&self.entries[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_history_keeps_entries() {
+        let mut history = History::default();
+        history.push(String::from("x"));
+        assert_eq!(history.entries().count(), 1);
+    }
+
+    #[test]
+    fn drops_oldest_entries_past_capacity() {
+        let mut history = History::new(2);
+        for entry in ["a", "b", "c"] {
+            history.push(entry.to_string());
+        }
+        assert_eq!(history.entries().collect::<Vec<_>>(), ["b", "c"]);
+    }
+
+    #[test]
+    fn recalls_by_prefix() {
+        let mut history = History::new(10);
+        for entry in ["cargo build", "git status", "cargo test"] {
+            history.push(entry.to_string());
+        }
+        assert_eq!(history.older("cargo"), Some("cargo test"));
+        assert_eq!(history.older("cargo test"), Some("cargo build"));
+        assert_eq!(history.newer().as_deref(), Some("cargo test"));
+        assert_eq!(history.newer().as_deref(), Some("cargo"));
+    }
+}
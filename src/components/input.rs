@@ -1,20 +1,58 @@
+use crate::components::keymap::{Edit, KeyAction, Keymap, Motion};
+use crate::components::{
+    CharFilter, Completer, History, InputMask, KeyBindings, Validator, VimMode,
+};
 use crate::{Component, Event, Spannable};
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt::{self, Debug};
+use std::sync::Arc;
 use tui::buffer::Buffer;
 use tui::layout::Rect;
-use tui::style::Style;
+use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
-use tui::widgets::{Paragraph, Widget};
+use tui::widgets::{Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget};
+use unicode_segmentation::UnicodeSegmentation;
 
-#[derive(Debug, Default, Clone)]
-// todo: add cursor
+/// The most completion candidates shown at once
+const MAX_SUGGESTIONS: u16 = 6;
+
+#[derive(Default, Clone)]
 pub struct Input {
     pub value: String,
+    /// position in graphemes, or `None` to follow the end of the value
+    cursor: Option<usize>,
+    keymap: Keymap,
     pub error: Option<String>,
     pub focused: bool,
+    /// Submitted values, recalled with Up/Down and searched with Ctrl-R
+    pub history: Option<History>,
     text_style: Style,
     editing_style: Style,
     error_style: Style,
+    validators: Vec<Validator>,
+    mask: Option<InputMask>,
+    filter: Option<CharFilter>,
+    secret: Option<Secret>,
+    reveal_key: Option<KeyEvent>,
+    revealed: bool,
+    placeholder: Option<String>,
+    placeholder_style: Style,
+    max_length: Option<usize>,
+    show_counter: bool,
+    completer: Option<Arc<dyn Completer + Send + Sync>>,
+    completions: Vec<String>,
+    /// the candidate highlighted in the popup
+    completion_index: Option<usize>,
+    ghost_text: bool,
+}
+
+/// How the value of a secret [`Input`] is displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    /// Each grapheme is replaced by the character
+    Masked(char),
+    /// Nothing is displayed at all
+    Hidden,
 }
 
 impl Input {
@@ -32,13 +70,514 @@ impl Input {
         self.error_style = style;
         self
     }
+
+    /// Adds a validator, evaluated in order on each edit and on submit
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Lays out the value according to a mask, which must be filled to submit
+    pub fn mask(mut self, mask: InputMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Only accepts typed characters allowed by the filter
+    pub fn char_filter(mut self, filter: CharFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Runs the validators against the current value, updating the error.
+    ///
+    /// Without any validators or mask, the error is left for the caller to manage
+    pub fn validate(&mut self) -> bool {
+        if self.validators.is_empty() && self.mask.is_none() {
+            return true;
+        }
+        self.error = self
+            .validators
+            .iter()
+            .find_map(|v| v.validate(&self.value).err());
+        self.error.is_none()
+    }
+
+    /// Sets dimmed text displayed while the value is empty
+    pub fn placeholder<S: Into<String>>(mut self, placeholder: S) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    pub fn placeholder_style(mut self, style: Style) -> Self {
+        self.placeholder_style = style;
+        self
+    }
+
+    /// Limits the number of graphemes that can be typed
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Displays the length of the value, along with the max length if set
+    pub fn show_counter(mut self, show_counter: bool) -> Self {
+        self.show_counter = show_counter;
+        self
+    }
+
+    /// Records submitted values, unless the input is secret
+    pub fn history(mut self, history: History) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Offers candidates from the completer in a popup below the input.
+    ///
+    /// Tab completes as far as the candidates agree, or accepts the only one. Up/Down
+    /// highlight a candidate, and Tab or Enter accepts it
+    pub fn completer<C: Completer + Send + Sync + 'static>(mut self, completer: C) -> Self {
+        self.completer = Some(Arc::new(completer));
+        self
+    }
+
+    /// Replaces the completer, such as when the candidates depend on outside state
+    pub fn set_completer<C: Completer + Send + Sync + 'static>(&mut self, completer: C) {
+        self.completer = Some(Arc::new(completer));
+        self.close_completions();
+    }
+
+    /// Shows the rest of the top candidate after the value, accepted with Right
+    pub fn ghost_text(mut self, ghost_text: bool) -> Self {
+        self.ghost_text = ghost_text;
+        self
+    }
+
+    /// The current completion candidates
+    pub fn completions(&self) -> &[String] {
+        &self.completions
+    }
+
+    /// Sets the keybinding profile used for editing
+    pub fn key_bindings(mut self, bindings: KeyBindings) -> Self {
+        self.keymap = Keymap::new(bindings);
+        self
+    }
+
+    /// The cursor position, in graphemes
+    pub fn cursor(&self) -> usize {
+        let mut cursor = self.cursor.unwrap_or(usize::MAX);
+        self.keymap.clamp_cursor(&self.value, &mut cursor);
+        cursor
+    }
+
+    /// Moves the cursor, with positions past the end following the end of the value
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = if cursor < self.value.graphemes(true).count() {
+            Some(cursor)
+        } else {
+            None
+        };
+    }
+
+    /// The vim mode, when using vim keybindings
+    pub fn vim_mode(&self) -> Option<VimMode> {
+        match self.keymap.bindings {
+            KeyBindings::Vim => Some(self.keymap.vim_mode),
+            _ => None,
+        }
+    }
+
+    /// Hides the value when displayed, such as for passwords
+    pub fn secret(mut self, secret: Secret) -> Self {
+        self.secret = Some(secret);
+        self
+    }
+
+    /// Sets a key which toggles between showing and hiding a secret value
+    pub fn reveal_key(mut self, key: KeyEvent) -> Self {
+        self.reveal_key = Some(key);
+        self
+    }
+
+    pub fn set_revealed(&mut self, revealed: bool) {
+        self.revealed = revealed;
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// The value as it should be displayed, respecting secret mode
+    fn display_value(&self) -> String {
+        match self.secret {
+            Some(_) if self.revealed => self.value.clone(),
+            Some(Secret::Masked(c)) => c.to_string().repeat(self.value.graphemes(true).count()),
+            Some(Secret::Hidden) => String::new(),
+            None => self.value.clone(),
+        }
+    }
+
+    fn placeholder_span<'b>(&self) -> Option<Span<'b>> {
+        match &self.placeholder {
+            Some(placeholder) if self.value.is_empty() => Some(Span::styled(
+                placeholder.clone(),
+                Style::default()
+                    .add_modifier(Modifier::DIM)
+                    .patch(self.placeholder_style),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The value with the cursor and any ghost text, while focused
+    fn editing_spans<'b>(&self) -> Vec<Span<'b>> {
+        let cursor_style = self.editing_style.add_modifier(Modifier::REVERSED);
+        let ghost_style = Style::default().add_modifier(Modifier::DIM);
+        if let Some(placeholder) = self.placeholder_span() {
+            return vec![Span::styled(" ", cursor_style), placeholder];
+        }
+        let display = self.display_value();
+        let graphemes = display.graphemes(true).collect::<Vec<_>>();
+        let cursor = self.cursor().min(graphemes.len());
+        let mut spans = vec![Span::styled(
+            graphemes[..cursor].concat(),
+            self.editing_style,
+        )];
+        if cursor < graphemes.len() {
+            spans.push(Span::styled(graphemes[cursor].to_string(), cursor_style));
+            spans.push(Span::styled(
+                graphemes[cursor + 1..].concat(),
+                self.editing_style,
+            ));
+        } else if let Some(ghost) = self.ghost_suffix().filter(|g| !g.is_empty()) {
+            // the cursor rests on the first character of the ghost text
+            let mut ghost = ghost.graphemes(true);
+            let first = ghost.next().unwrap_or_default();
+            spans.push(Span::styled(
+                first.to_string(),
+                ghost_style.patch(cursor_style),
+            ));
+            spans.push(Span::styled(ghost.collect::<String>(), ghost_style));
+        } else {
+            spans.push(Span::styled(" ", cursor_style));
+        }
+        spans
+    }
+
+    fn counter_span<'b>(&self) -> Option<Span<'b>> {
+        // a hidden secret shouldn't give away its length
+        if !self.show_counter || (self.secret == Some(Secret::Hidden) && !self.revealed) {
+            return None;
+        }
+        let len = self.value.graphemes(true).count();
+        let counter = match self.max_length {
+            Some(max) => format!(" {}/{}", len, max),
+            None => format!(" {}", len),
+        };
+        Some(Span::styled(
+            counter,
+            Style::default().add_modifier(Modifier::DIM),
+        ))
+    }
+
+    fn update_completions(&mut self) {
+        self.completion_index = None;
+        self.completions = match &self.completer {
+            // candidates would give away a secret value
+            Some(_) if self.secret.is_some() => Vec::new(),
+            Some(completer) => completer
+                .complete(&self.value)
+                .into_iter()
+                .filter(|candidate| candidate != &self.value && self.fits(candidate))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    fn close_completions(&mut self) {
+        self.completions.clear();
+        self.completion_index = None;
+    }
+
+    fn move_completion(&mut self, forward: bool) {
+        let last = self.completions.len() - 1;
+        self.completion_index = Some(match (self.completion_index, forward) {
+            (None, true) => 0,
+            (None, false) => last,
+            (Some(i), true) => {
+                if i >= last {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            (Some(i), false) => {
+                if i == 0 {
+                    last
+                } else {
+                    i - 1
+                }
+            }
+        });
+    }
+
+    /// Replaces the value with the highlighted candidate, or the top one
+    fn accept_completion(&mut self) -> InputResponse {
+        let candidate = self.completions[self.completion_index.unwrap_or(0)].clone();
+        if !self.fits(&candidate) {
+            return InputResponse::None;
+        }
+        self.value = candidate;
+        self.cursor = None;
+        self.history.iter_mut().for_each(History::reset);
+        self.update_completions();
+        self.validate();
+        InputResponse::Edited { deletion: false }
+    }
+
+    /// Extends the value to the start every candidate shares, accepting it when it is
+    /// the only one. Ambiguous candidates stay listed
+    fn complete_common_prefix(&mut self) -> InputResponse {
+        if self.completions.len() == 1 {
+            return self.accept_completion();
+        }
+        let prefix = common_prefix(&self.completions);
+        let extends = prefix.chars().count() > self.value.chars().count()
+            && prefix
+                .to_lowercase()
+                .starts_with(&self.value.to_lowercase());
+        if !extends || !self.fits(prefix) {
+            return InputResponse::None;
+        }
+        self.value = prefix.to_string();
+        self.cursor = None;
+        self.history.iter_mut().for_each(History::reset);
+        self.update_completions();
+        self.validate();
+        InputResponse::Edited { deletion: false }
+    }
+
+    /// The remainder of the top candidate, if it extends the value
+    fn ghost_suffix(&self) -> Option<&str> {
+        if !self.ghost_text || self.secret.is_some() {
+            return None;
+        }
+        self.completions
+            .get(self.completion_index.unwrap_or(0))
+            .and_then(|candidate| candidate.strip_prefix(self.value.as_str()))
+    }
+
+    /// Draws the completion popup below an input drawn at `rect`, while focused.
+    ///
+    /// [`Component::draw`] draws it too, but a container which draws over the area below
+    /// the input should call this again afterwards
+    pub fn draw_completions(&self, rect: Rect, buf: &mut Buffer) {
+        if !self.focused || self.completions.is_empty() {
+            return;
+        }
+        let area = buf.area;
+        // the popup sits below the first line, lined up after the "> " prefix
+        let y = rect.y + 1;
+        let x = rect.x + 2;
+        if y >= area.bottom() || x >= area.right() {
+            return;
+        }
+        let width = self
+            .completions
+            .iter()
+            .map(|c| Span::raw(c.as_str()).width() + 2)
+            .max()
+            .unwrap_or(0) as u16;
+        let popup = Rect {
+            x,
+            y,
+            width: width.min(area.right() - x),
+            height: (self.completions.len() as u16)
+                .min(MAX_SUGGESTIONS)
+                .min(area.bottom() - y),
+        };
+        let items = self
+            .completions
+            .iter()
+            .map(|c| ListItem::new(format!(" {} ", c)))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .style(Style::default().bg(Color::DarkGray))
+            .highlight_style(Style::default().bg(Color::Blue));
+        let mut state = ListState::default();
+        state.select(self.completion_index);
+        Widget::render(Clear, popup, buf);
+        StatefulWidget::render(list, popup, buf, &mut state);
+    }
+
+    /// Replaces the value with one recalled from the history
+    fn recall(&mut self, value: Option<String>) -> InputResponse {
+        match value {
+            Some(value) if self.fits(&value) => {
+                self.value = value;
+                self.cursor = None;
+                self.close_completions();
+                self.validate();
+                InputResponse::Edited { deletion: false }
+            }
+            _ => InputResponse::None,
+        }
+    }
+
+    /// Whether a whole value respects the max length, character filter and mask,
+    /// as typed values do
+    fn fits(&self, value: &str) -> bool {
+        if self
+            .max_length
+            .is_some_and(|max| value.graphemes(true).count() > max)
+        {
+            return false;
+        }
+        if let Some(filter) = &self.filter {
+            if !value.chars().all(|c| filter.allows(c)) {
+                return false;
+            }
+        }
+        match &self.mask {
+            Some(mask) => mask.format(&mask.unformat(value)).as_deref() == Some(value),
+            None => true,
+        }
+    }
+
+    /// Validates the value, and checks that the mask has been filled
+    fn validate_submit(&mut self) -> bool {
+        if !self.validate() {
+            return false;
+        }
+        match &self.mask {
+            Some(mask) if !mask.is_complete(&self.value) => {
+                self.error = Some(String::from("incomplete value"));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Inserts a typed character, returning whether it was accepted
+    fn insert_char(&mut self, c: char) -> bool {
+        if let Some(filter) = &self.filter {
+            if !filter.allows(c) {
+                return false;
+            }
+        }
+        let mut cursor = self.cursor();
+        let value = if let Some(mask) = &self.mask {
+            let mut raw = mask.unformat(&self.value);
+            raw.push(c);
+            cursor = usize::MAX;
+            match mask.format(&raw) {
+                Some(formatted) => formatted,
+                None => return false,
+            }
+        } else {
+            let mut value = self.value.clone();
+            self.keymap.apply(Edit::Insert(c), &mut value, &mut cursor);
+            value
+        };
+        if let Some(max) = self.max_length {
+            if value.graphemes(true).count() > max {
+                return false;
+            }
+        }
+        self.value = value;
+        self.set_cursor(cursor);
+        true
+    }
+
+    /// Applies an edit at the cursor, returning whether the value changed.
+    ///
+    /// Masked values are only edited at the end, by typing and deleting
+    fn apply_edit(&mut self, edit: Edit) -> bool {
+        match (edit, &self.mask) {
+            (Edit::Insert(c), _) => self.insert_char(c),
+            (Edit::Delete(Motion::Left), Some(mask)) => {
+                let mut raw = mask.unformat(&self.value);
+                let deleted = raw.pop().is_some();
+                self.value = mask.format(&raw).unwrap_or_default();
+                deleted
+            }
+            (_, Some(_)) => false,
+            (_, None) => {
+                let mut value = self.value.clone();
+                let mut cursor = self.cursor();
+                let changed = self.keymap.apply(edit, &mut value, &mut cursor);
+                if self.secret.is_some() {
+                    self.keymap.clear_kill_buffer();
+                }
+                // yanked text goes through the same checks as typed text
+                if matches!(edit, Edit::Yank | Edit::YankAfter) && !self.fits(&value) {
+                    return false;
+                }
+                self.value = value;
+                self.set_cursor(cursor);
+                changed
+            }
+        }
+    }
+
+    fn edit(&mut self, edit: Edit) -> InputResponse {
+        if !self.apply_edit(edit) {
+            return InputResponse::None;
+        }
+        self.history.iter_mut().for_each(History::reset);
+        self.update_completions();
+        self.validate();
+        InputResponse::Edited {
+            deletion: !matches!(edit, Edit::Insert(_) | Edit::Yank | Edit::YankAfter),
+        }
+    }
+}
+
+impl Debug for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (value, completions): (&dyn Debug, &dyn Debug) = if self.secret.is_some() {
+            (&"<secret>", &"<secret>")
+        } else {
+            (&self.value, &self.completions)
+        };
+        f.debug_struct("Input")
+            .field("value", value)
+            .field("cursor", &self.cursor)
+            .field("keymap", &self.keymap)
+            .field("error", &self.error)
+            .field("focused", &self.focused)
+            .field("history", &self.history)
+            .field("text_style", &self.text_style)
+            .field("editing_style", &self.editing_style)
+            .field("error_style", &self.error_style)
+            .field("validators", &self.validators)
+            .field("mask", &self.mask)
+            .field("filter", &self.filter)
+            .field("secret", &self.secret)
+            .field("reveal_key", &self.reveal_key)
+            .field("revealed", &self.revealed)
+            .field("placeholder", &self.placeholder)
+            .field("placeholder_style", &self.placeholder_style)
+            .field("max_length", &self.max_length)
+            .field("show_counter", &self.show_counter)
+            .field("completer", &self.completer.as_ref().map(|_| ".."))
+            .field("completions", completions)
+            .field("completion_index", &self.completion_index)
+            .field("ghost_text", &self.ghost_text)
+            .finish()
+    }
 }
 
 #[derive(Debug)]
 pub enum InputResponse {
     None,
-    Edited { deletion: bool },
+    Edited {
+        deletion: bool,
+    },
     Submit,
+    /// Submit was attempted, but the value failed validation
+    Invalid,
     Cancel,
 }
 
@@ -48,16 +587,70 @@ impl Component for Input {
 
     fn handle_event(&mut self, event: Event) -> Self::Response {
         if let Event::Key(key_event) = event {
-            match key_event.code {
-                KeyCode::Char(c) => {
-                    self.value.push(c);
-                    InputResponse::Edited { deletion: false }
+            if self.secret.is_some() && self.reveal_key == Some(key_event) {
+                self.revealed = !self.revealed;
+                return InputResponse::None;
+            }
+            if !self.completions.is_empty() {
+                match key_event.code {
+                    KeyCode::Up => {
+                        self.move_completion(false);
+                        return InputResponse::None;
+                    }
+                    KeyCode::Down => {
+                        self.move_completion(true);
+                        return InputResponse::None;
+                    }
+                    KeyCode::Tab if self.completion_index.is_none() => {
+                        return self.complete_common_prefix()
+                    }
+                    KeyCode::Tab => return self.accept_completion(),
+                    KeyCode::Enter if self.completion_index.is_some() => {
+                        return self.accept_completion()
+                    }
+                    KeyCode::Right
+                        if self.ghost_suffix().is_some()
+                            && self.cursor() == self.value.graphemes(true).count() =>
+                    {
+                        return self.accept_completion()
+                    }
+                    KeyCode::Esc => {
+                        self.close_completions();
+                        return InputResponse::None;
+                    }
+                    _ => {}
                 }
-                KeyCode::Backspace => {
-                    self.value.pop();
-                    InputResponse::Edited { deletion: true }
+            }
+            let key_event = match self.keymap.translate(key_event) {
+                KeyAction::Edit(edit) => return self.edit(edit),
+                KeyAction::Forward(key) => key,
+                KeyAction::Consumed => return InputResponse::None,
+            };
+            if let Some(history) = &mut self.history {
+                let recalled = match key_event.code {
+                    KeyCode::Up => Some(history.older(&self.value).map(String::from)),
+                    KeyCode::Down => Some(history.newer()),
+                    KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(history.search(&self.value).map(String::from))
+                    }
+                    _ => None,
+                };
+                if let Some(value) = recalled {
+                    return self.recall(value);
+                }
+            }
+            match key_event.code {
+                KeyCode::Enter => {
+                    self.close_completions();
+                    if self.validate_submit() {
+                        if let (Some(history), None) = (&mut self.history, self.secret) {
+                            history.push(self.value.clone());
+                        }
+                        InputResponse::Submit
+                    } else {
+                        InputResponse::Invalid
+                    }
                 }
-                KeyCode::Enter => InputResponse::Submit,
                 KeyCode::Esc => InputResponse::Cancel,
                 _ => InputResponse::None,
             }
@@ -69,17 +662,21 @@ impl Component for Input {
     fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
         let p = Paragraph::new(self.get_spans());
         p.render(rect, buf);
+        self.draw_completions(rect, buf);
     }
 }
 
 impl Spannable for Input {
-    fn get_spans<'a, 'b>(&'a self) -> Spans<'b> {
+    fn get_spans<'b>(&self) -> Spans<'b> {
         let mut spans = Spans::default();
         if self.focused {
-            spans.0.push(Span::raw("> "));
-            spans
-                .0
-                .push(Span::styled(self.value.clone(), self.editing_style));
+            spans.0.push(Span::raw(if self.keymap.is_normal_mode() {
+                ": "
+            } else {
+                "> "
+            }));
+            spans.0.extend(self.editing_spans());
+            spans.0.extend(self.counter_span());
             if let Some(e) = &self.error {
                 spans
                     .0
@@ -91,8 +688,178 @@ impl Spannable for Input {
             } else {
                 self.text_style
             };
-            spans.0.push(Span::styled(self.value.clone(), style));
+            match self.placeholder_span() {
+                Some(placeholder) => spans.0.push(placeholder),
+                None => spans.0.push(Span::styled(self.display_value(), style)),
+            }
+            spans.0.extend(self.counter_span());
         }
         spans
     }
 }
+
+/// The longest start shared by all candidates
+fn common_prefix(candidates: &[String]) -> &str {
+    let first = match candidates.first() {
+        Some(first) => first,
+        None => return "",
+    };
+    let len = candidates[1..].iter().fold(first.len(), |len, candidate| {
+        first[..len]
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(candidate.len()), |((i, _), _)| i)
+    });
+    &first[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{MemoryFileSystem, PathCompleter, WordCompleter};
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn completions_respect_max_length_and_filter() {
+        let mut input = Input::default()
+            .max_length(4)
+            .char_filter(CharFilter::chars("ab"))
+            .completer(WordCompleter::new(["ab", "abab", "ababab", "abc"]));
+        input.handle_event(key(KeyCode::Char('a')));
+        assert_eq!(input.completions(), ["ab", "abab"]);
+        input.handle_event(key(KeyCode::Down));
+        input.handle_event(key(KeyCode::Down));
+        input.handle_event(key(KeyCode::Tab));
+        assert_eq!(input.value, "abab");
+    }
+
+    #[test]
+    fn tab_completes_the_common_prefix() {
+        let mut input = Input::default().completer(WordCompleter::new(["foo_a", "foo_b", "bar"]));
+        input.handle_event(key(KeyCode::Char('f')));
+        input.handle_event(key(KeyCode::Tab));
+        assert_eq!(input.value, "foo_");
+        assert_eq!(input.completions(), ["foo_a", "foo_b"]);
+        // the candidates still differ, so they stay listed
+        assert!(matches!(
+            input.handle_event(key(KeyCode::Tab)),
+            InputResponse::None
+        ));
+        assert_eq!(input.value, "foo_");
+        input.handle_event(key(KeyCode::Down));
+        input.handle_event(key(KeyCode::Tab));
+        assert_eq!(input.value, "foo_a");
+
+        let mut input = Input::default().completer(WordCompleter::new(["foo_a", "bar"]));
+        input.handle_event(key(KeyCode::Char('f')));
+        input.handle_event(key(KeyCode::Tab));
+        assert_eq!(input.value, "foo_a");
+    }
+
+    #[test]
+    fn tab_completes_paths_as_far_as_they_agree() {
+        let fs = MemoryFileSystem::new()
+            .directory("/base/foo_a")
+            .directory("/base/foo_b");
+        let mut input =
+            Input::default().completer(PathCompleter::with_file_system("/base", Arc::new(fs)));
+        for c in "./f".chars() {
+            input.handle_event(key(KeyCode::Char(c)));
+        }
+        input.handle_event(key(KeyCode::Tab));
+        assert_eq!(input.value, "./foo_");
+        assert_eq!(input.completions().len(), 2);
+    }
+
+    #[test]
+    fn finds_common_prefixes() {
+        let candidates = |c: &[&str]| c.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        assert_eq!(common_prefix(&candidates(&["abc", "abd", "ab"])), "ab");
+        assert_eq!(common_prefix(&candidates(&["héllo", "hél", "hé!"])), "hé");
+        assert_eq!(common_prefix(&candidates(&["a", "b"])), "");
+        assert_eq!(common_prefix(&[]), "");
+    }
+
+    #[test]
+    fn recall_skips_values_over_max_length() {
+        let mut history = History::new(10);
+        history.push(String::from("too long"));
+        let mut input = Input::default().max_length(4).history(history);
+        input.handle_event(key(KeyCode::Up));
+        assert_eq!(input.value, "");
+    }
+
+    fn ctrl(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    #[test]
+    fn yank_respects_max_length_and_filter() {
+        let mut input = Input::default()
+            .key_bindings(KeyBindings::Emacs)
+            .max_length(4)
+            .char_filter(CharFilter::chars("ab"));
+        input.handle_event(key(KeyCode::Char('a')));
+        input.handle_event(key(KeyCode::Char('b')));
+        input.handle_event(ctrl('a'));
+        input.handle_event(ctrl('k'));
+        for _ in 0..3 {
+            input.handle_event(ctrl('y'));
+        }
+        assert_eq!(input.value, "abab");
+    }
+
+    #[test]
+    fn secret_kills_are_not_kept() {
+        let mut input = Input::default()
+            .key_bindings(KeyBindings::Emacs)
+            .secret(Secret::Hidden);
+        for c in "hunter2".chars() {
+            input.handle_event(key(KeyCode::Char(c)));
+        }
+        input.handle_event(ctrl('u'));
+        assert!(!format!("{:?}", input).contains("hunter2"));
+        input.handle_event(ctrl('y'));
+        assert_eq!(input.value, "");
+    }
+
+    #[test]
+    fn submits_only_valid_values() {
+        let mut input = Input::default()
+            .validator(Validator::pattern("[a-z]+", "letters only").unwrap())
+            .validator(Validator::length(Some(2), None));
+        input.handle_event(key(KeyCode::Char('a')));
+        assert!(matches!(
+            input.handle_event(key(KeyCode::Enter)),
+            InputResponse::Invalid
+        ));
+        assert!(input.error.is_some());
+        input.handle_event(key(KeyCode::Char('1')));
+        assert!(matches!(
+            input.handle_event(key(KeyCode::Enter)),
+            InputResponse::Invalid
+        ));
+        assert_eq!(input.error.as_deref(), Some("letters only"));
+        input.handle_event(key(KeyCode::Backspace));
+        input.handle_event(key(KeyCode::Char('b')));
+        assert!(matches!(
+            input.handle_event(key(KeyCode::Enter)),
+            InputResponse::Submit
+        ));
+        assert_eq!(input.error, None);
+    }
+
+    #[test]
+    fn secret_values_are_not_completed() {
+        let mut input = Input::default()
+            .secret(Secret::Masked('*'))
+            .completer(WordCompleter::new(["hunter2"]));
+        input.handle_event(key(KeyCode::Char('h')));
+        assert!(input.completions().is_empty());
+        assert!(!format!("{:?}", input).contains("hunter2"));
+    }
+}
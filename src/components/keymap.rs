@@ -0,0 +1,499 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt::{self, Debug};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The keybinding profile used by text fields such as [`Input`](crate::components::Input)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyBindings {
+    /// Arrow keys, Home/End, Backspace and Delete
+    #[default]
+    Standard,
+    /// `C-a`, `C-e`, `C-f`, `C-b`, `M-f`, `M-b`, `C-d`, `C-k`, `C-u`, `C-w`, `M-d`, `C-y`
+    Emacs,
+    /// Modal editing, starting in insert mode. Esc enters normal mode, which supports
+    /// `h`, `l`, `w`, `b`, `0`, `$`, `x`, `X`, `i`, `a`, `I`, `A`, `D`, `C`, `S`, `p`, `P`,
+    /// and the `d` and `c` operators with a motion, `iw`, or doubled for the whole line
+    Vim,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VimMode {
+    Normal,
+    #[default]
+    Insert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Motion {
+    Left,
+    Right,
+    Start,
+    End,
+    /// the start of the next word
+    WordForward,
+    /// the start of the current or previous word
+    WordBackward,
+    /// just past the end of the current or next word
+    WordEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    Insert(char),
+    Move(Motion),
+    /// Removes the text between the cursor and the motion's target
+    Delete(Motion),
+    /// Removes the text between the cursor and the motion's target, keeping it to yank
+    Kill(Motion),
+    /// Kills the word (or whitespace) under the cursor
+    KillInnerWord,
+    KillLine,
+    /// Inserts the killed text before the cursor
+    Yank,
+    /// Inserts the killed text after the cursor
+    YankAfter,
+    /// Steps back onto the last character, when leaving vim's insert mode
+    LeaveInsert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyAction {
+    Edit(Edit),
+    /// The key should be handled by the component, as if it were this key
+    Forward(KeyEvent),
+    /// The key only changed the state of the keymap
+    Consumed,
+}
+
+/// Translates keys into edits according to a [`KeyBindings`] profile
+#[derive(Clone, Default)]
+pub(crate) struct Keymap {
+    pub bindings: KeyBindings,
+    pub vim_mode: VimMode,
+    /// a vim operator waiting for its motion, and whether `i` followed it
+    pending: Option<(char, bool)>,
+    kill_buffer: String,
+}
+
+impl Debug for Keymap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // killed text may come from a secret input
+        f.debug_struct("Keymap")
+            .field("bindings", &self.bindings)
+            .field("vim_mode", &self.vim_mode)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Keymap {
+    pub fn new(bindings: KeyBindings) -> Self {
+        Self {
+            bindings,
+            ..Default::default()
+        }
+    }
+
+    /// Forgets killed text, so that it can't be yanked
+    pub fn clear_kill_buffer(&mut self) {
+        self.kill_buffer.clear();
+    }
+
+    /// Whether the cursor rests on a character, rather than between them
+    pub fn is_normal_mode(&self) -> bool {
+        self.bindings == KeyBindings::Vim && self.vim_mode == VimMode::Normal
+    }
+
+    pub fn translate(&mut self, key: KeyEvent) -> KeyAction {
+        match self.bindings {
+            KeyBindings::Standard => Self::standard(key),
+            KeyBindings::Emacs => Self::emacs(key),
+            KeyBindings::Vim => match self.vim_mode {
+                VimMode::Insert if key.code == KeyCode::Esc => {
+                    self.vim_mode = VimMode::Normal;
+                    KeyAction::Edit(Edit::LeaveInsert)
+                }
+                VimMode::Insert => Self::standard(key),
+                VimMode::Normal => self.vim_normal(key),
+            },
+        }
+    }
+
+    fn standard(key: KeyEvent) -> KeyAction {
+        let edit = match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                Edit::Insert(c)
+            }
+            KeyCode::Backspace => Edit::Delete(Motion::Left),
+            KeyCode::Delete => Edit::Delete(Motion::Right),
+            KeyCode::Left if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Edit::Move(Motion::WordBackward)
+            }
+            KeyCode::Right if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Edit::Move(Motion::WordEnd)
+            }
+            KeyCode::Left => Edit::Move(Motion::Left),
+            KeyCode::Right => Edit::Move(Motion::Right),
+            KeyCode::Home => Edit::Move(Motion::Start),
+            KeyCode::End => Edit::Move(Motion::End),
+            _ => return KeyAction::Forward(key),
+        };
+        KeyAction::Edit(edit)
+    }
+
+    fn emacs(key: KeyEvent) -> KeyAction {
+        let forward = |code| KeyAction::Forward(KeyEvent::new(code, KeyModifiers::NONE));
+        let edit = if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('a') => Edit::Move(Motion::Start),
+                KeyCode::Char('e') => Edit::Move(Motion::End),
+                KeyCode::Char('f') => Edit::Move(Motion::Right),
+                KeyCode::Char('b') => Edit::Move(Motion::Left),
+                KeyCode::Char('d') => Edit::Delete(Motion::Right),
+                KeyCode::Char('h') => Edit::Delete(Motion::Left),
+                KeyCode::Char('k') => Edit::Kill(Motion::End),
+                KeyCode::Char('u') => Edit::Kill(Motion::Start),
+                KeyCode::Char('w') => Edit::Kill(Motion::WordBackward),
+                KeyCode::Char('y') => Edit::Yank,
+                KeyCode::Char('p') => return forward(KeyCode::Up),
+                KeyCode::Char('n') => return forward(KeyCode::Down),
+                KeyCode::Char('g') => return forward(KeyCode::Esc),
+                _ => return Self::standard(key),
+            }
+        } else if key.modifiers.contains(KeyModifiers::ALT) {
+            match key.code {
+                KeyCode::Char('f') => Edit::Move(Motion::WordEnd),
+                KeyCode::Char('b') => Edit::Move(Motion::WordBackward),
+                KeyCode::Char('d') => Edit::Kill(Motion::WordEnd),
+                KeyCode::Backspace => Edit::Kill(Motion::WordBackward),
+                _ => return Self::standard(key),
+            }
+        } else {
+            return Self::standard(key);
+        };
+        KeyAction::Edit(edit)
+    }
+
+    fn vim_normal(&mut self, key: KeyEvent) -> KeyAction {
+        let forward = |code| KeyAction::Forward(KeyEvent::new(code, KeyModifiers::NONE));
+        let c = match key.code {
+            KeyCode::Char(c) => c,
+            KeyCode::Left | KeyCode::Backspace => 'h',
+            KeyCode::Right => 'l',
+            KeyCode::Home => '0',
+            KeyCode::End => '$',
+            KeyCode::Delete => 'x',
+            _ => {
+                self.pending = None;
+                return KeyAction::Forward(key);
+            }
+        };
+        let motion = match c {
+            'h' => Some(Motion::Left),
+            'l' => Some(Motion::Right),
+            '0' => Some(Motion::Start),
+            '$' => Some(Motion::End),
+            'w' => Some(Motion::WordForward),
+            'b' => Some(Motion::WordBackward),
+            _ => None,
+        };
+
+        if let Some((operator, inner)) = self.pending.take() {
+            let edit = match (c, motion, inner) {
+                ('w', _, true) => Edit::KillInnerWord,
+                (_, _, true) => return KeyAction::Consumed,
+                ('i', _, false) => {
+                    self.pending = Some((operator, true));
+                    return KeyAction::Consumed;
+                }
+                // like vim, `cw` changes to the end of the word and leaves the space after it
+                ('w', _, false) if operator == 'c' => Edit::Kill(Motion::WordEnd),
+                (_, Some(motion), false) => Edit::Kill(motion),
+                _ if c == operator => Edit::KillLine,
+                _ => return KeyAction::Consumed,
+            };
+            if operator == 'c' {
+                self.vim_mode = VimMode::Insert;
+            }
+            return KeyAction::Edit(edit);
+        }
+
+        if let Some(motion) = motion {
+            return KeyAction::Edit(Edit::Move(motion));
+        }
+        let edit = match c {
+            'd' | 'c' => {
+                self.pending = Some((c, false));
+                return KeyAction::Consumed;
+            }
+            'i' => {
+                self.vim_mode = VimMode::Insert;
+                return KeyAction::Consumed;
+            }
+            'a' => {
+                self.vim_mode = VimMode::Insert;
+                Edit::Move(Motion::Right)
+            }
+            'I' => {
+                self.vim_mode = VimMode::Insert;
+                Edit::Move(Motion::Start)
+            }
+            'A' => {
+                self.vim_mode = VimMode::Insert;
+                Edit::Move(Motion::End)
+            }
+            'x' => Edit::Kill(Motion::Right),
+            'X' => Edit::Kill(Motion::Left),
+            'D' => Edit::Kill(Motion::End),
+            'C' => {
+                self.vim_mode = VimMode::Insert;
+                Edit::Kill(Motion::End)
+            }
+            'S' => {
+                self.vim_mode = VimMode::Insert;
+                Edit::KillLine
+            }
+            'p' => Edit::YankAfter,
+            'P' => Edit::Yank,
+            'j' => return forward(KeyCode::Down),
+            'k' => return forward(KeyCode::Up),
+            _ => return KeyAction::Consumed,
+        };
+        KeyAction::Edit(edit)
+    }
+
+    /// Applies an edit to a single line, with the cursor counted in graphemes.
+    ///
+    /// Returns whether the text changed
+    pub fn apply(&mut self, edit: Edit, line: &mut String, cursor: &mut usize) -> bool {
+        let len = line.graphemes(true).count();
+        *cursor = (*cursor).min(len);
+        let changed = match edit {
+            Edit::Insert(c) => {
+                let at = byte_index(line, *cursor);
+                line.insert(at, c);
+                // the character may merge with the grapheme before it
+                *cursor = line[..at + c.len_utf8()].graphemes(true).count();
+                true
+            }
+            Edit::Move(motion) => {
+                *cursor = target(line, *cursor, motion);
+                false
+            }
+            Edit::Delete(motion) | Edit::Kill(motion) => {
+                let other = target(line, *cursor, motion);
+                let (start, end) = (other.min(*cursor), other.max(*cursor));
+                let removed = remove(line, start, end);
+                if matches!(edit, Edit::Kill(_)) && !removed.is_empty() {
+                    self.kill_buffer = removed;
+                }
+                *cursor = start;
+                start != end
+            }
+            Edit::KillInnerWord => {
+                let (start, end) = word_bounds(line, *cursor);
+                self.kill_buffer = remove(line, start, end);
+                *cursor = start;
+                start != end
+            }
+            Edit::KillLine => {
+                self.kill_buffer = std::mem::take(line);
+                *cursor = 0;
+                len > 0
+            }
+            Edit::Yank | Edit::YankAfter => {
+                if edit == Edit::YankAfter && len > 0 {
+                    *cursor += 1;
+                }
+                let at = byte_index(line, *cursor);
+                line.insert_str(at, &self.kill_buffer);
+                *cursor = line[..at + self.kill_buffer.len()].graphemes(true).count();
+                if self.is_normal_mode() {
+                    // vim leaves the cursor on the last pasted character
+                    *cursor = cursor.saturating_sub(1);
+                }
+                !self.kill_buffer.is_empty()
+            }
+            Edit::LeaveInsert => {
+                *cursor = cursor.saturating_sub(1);
+                false
+            }
+        };
+        self.clamp_cursor(line, cursor);
+        changed
+    }
+
+    /// Keeps the cursor on a character while in vim's normal mode
+    pub fn clamp_cursor(&self, line: &str, cursor: &mut usize) {
+        let len = line.graphemes(true).count();
+        *cursor = if self.is_normal_mode() {
+            (*cursor).min(len.saturating_sub(1))
+        } else {
+            (*cursor).min(len)
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn class(grapheme: &str) -> CharClass {
+    match grapheme.chars().next() {
+        Some(c) if c.is_whitespace() => CharClass::Space,
+        Some(c) if c.is_alphanumeric() || c == '_' => CharClass::Word,
+        _ => CharClass::Punctuation,
+    }
+}
+
+/// Where a motion moves the cursor to
+fn target(line: &str, cursor: usize, motion: Motion) -> usize {
+    let classes = line.graphemes(true).map(class).collect::<Vec<_>>();
+    let len = classes.len();
+    match motion {
+        Motion::Left => cursor.saturating_sub(1),
+        Motion::Right => (cursor + 1).min(len),
+        Motion::Start => 0,
+        Motion::End => len,
+        Motion::WordForward => {
+            let mut i = cursor;
+            if i < len && classes[i] != CharClass::Space {
+                let current = classes[i];
+                while i < len && classes[i] == current {
+                    i += 1;
+                }
+            }
+            while i < len && classes[i] == CharClass::Space {
+                i += 1;
+            }
+            i
+        }
+        Motion::WordBackward => {
+            let mut i = cursor;
+            while i > 0 && classes[i - 1] == CharClass::Space {
+                i -= 1;
+            }
+            if i > 0 {
+                let current = classes[i - 1];
+                while i > 0 && classes[i - 1] == current {
+                    i -= 1;
+                }
+            }
+            i
+        }
+        Motion::WordEnd => {
+            let mut i = cursor;
+            while i < len && classes[i] == CharClass::Space {
+                i += 1;
+            }
+            if i < len {
+                let current = classes[i];
+                while i < len && classes[i] == current {
+                    i += 1;
+                }
+            }
+            i
+        }
+    }
+}
+
+/// The range of the run of same-class graphemes under the cursor
+fn word_bounds(line: &str, cursor: usize) -> (usize, usize) {
+    let classes = line.graphemes(true).map(class).collect::<Vec<_>>();
+    if cursor >= classes.len() {
+        return (cursor, cursor);
+    }
+    let current = classes[cursor];
+    let mut start = cursor;
+    while start > 0 && classes[start - 1] == current {
+        start -= 1;
+    }
+    let mut end = cursor;
+    while end < classes.len() && classes[end] == current {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// Removes the graphemes from `start` up to `end`, returning them
+fn remove(line: &mut String, start: usize, end: usize) -> String {
+    let range = byte_index(line, start)..byte_index(line, end);
+    let removed = line[range.clone()].to_string();
+    line.replace_range(range, "");
+    removed
+}
+
+/// The byte index of the grapheme at `column`, or the end of the line
+pub(crate) fn byte_index(line: &str, column: usize) -> usize {
+    line.grapheme_indices(true)
+        .nth(column)
+        .map_or(line.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_merging_graphemes() {
+        let mut keymap = Keymap::default();
+        let mut line = String::from("👨👩");
+        let mut cursor = 1;
+        assert!(keymap.apply(Edit::Insert('\u{200d}'), &mut line, &mut cursor));
+        assert_eq!(line, "👨\u{200d}👩");
+        assert_eq!(cursor, 1);
+    }
+
+    #[test]
+    fn debug_hides_killed_text() {
+        let mut keymap = Keymap::default();
+        let mut line = String::from("hunter2");
+        let mut cursor = 7;
+        keymap.apply(Edit::Kill(Motion::Start), &mut line, &mut cursor);
+        assert!(!format!("{:?}", keymap).contains("hunter2"));
+    }
+
+    #[test]
+    fn moves_by_words() {
+        let line = "foo.bar  baz";
+        assert_eq!(target(line, 0, Motion::WordForward), 3);
+        assert_eq!(target(line, 3, Motion::WordForward), 4);
+        assert_eq!(target(line, 4, Motion::WordForward), 9);
+        assert_eq!(target(line, 9, Motion::WordForward), 12);
+        assert_eq!(target(line, 12, Motion::WordBackward), 9);
+        assert_eq!(target(line, 9, Motion::WordBackward), 4);
+        assert_eq!(target(line, 4, Motion::WordBackward), 3);
+        assert_eq!(target(line, 4, Motion::WordEnd), 7);
+        assert_eq!(target(line, 7, Motion::WordEnd), 12);
+    }
+
+    #[test]
+    fn moves_by_graphemes() {
+        let line = "ae\u{301}👨\u{200d}👩";
+        assert_eq!(target(line, 0, Motion::Right), 1);
+        assert_eq!(target(line, 2, Motion::Right), 3);
+        assert_eq!(target(line, 3, Motion::Right), 3);
+        assert_eq!(target(line, 0, Motion::Left), 0);
+        assert_eq!(target(line, 2, Motion::End), 3);
+        assert_eq!(target(line, 2, Motion::Start), 0);
+    }
+
+    #[test]
+    fn kills_and_yanks() {
+        let mut keymap = Keymap::new(KeyBindings::Emacs);
+        let mut line = String::from("one two");
+        let mut cursor = 7;
+        keymap.apply(Edit::Kill(Motion::WordBackward), &mut line, &mut cursor);
+        assert_eq!((line.as_str(), cursor), ("one ", 4));
+        keymap.apply(Edit::Move(Motion::Start), &mut line, &mut cursor);
+        keymap.apply(Edit::Yank, &mut line, &mut cursor);
+        assert_eq!((line.as_str(), cursor), ("twoone ", 3));
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+/// A single position in an [`InputMask`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskSlot {
+    /// `#`: an ASCII digit
+    Digit,
+    /// `A`: an alphabetic character
+    Letter,
+    /// `*`: an alphanumeric character
+    Alphanumeric,
+    /// `H`: an ASCII hexadecimal digit
+    Hex,
+    /// `?`: any character
+    Any,
+    /// A character inserted automatically
+    Literal(char),
+}
+
+impl MaskSlot {
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Letter => c.is_alphabetic(),
+            MaskSlot::Alphanumeric => c.is_alphanumeric(),
+            MaskSlot::Hex => c.is_ascii_hexdigit(),
+            MaskSlot::Any => true,
+            MaskSlot::Literal(_) => false,
+        }
+    }
+}
+
+/// A layout for an [`Input`](crate::components::Input) value.
+///
+/// Literal characters are inserted automatically as the user types,
+/// and each remaining position only accepts its class of characters.
+/// Optional positions let a segment be shorter, ended by typing the literal after it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMask {
+    slots: Vec<MaskSlot>,
+    /// whether each slot may be left out
+    optional: Vec<bool>,
+}
+
+impl InputMask {
+    /// Parses a mask pattern, such as `(###) ###-####`, `HH:HH:HH:HH:HH:HH`
+    /// or `#[##].#[##].#[##].#[##]`.
+    ///
+    /// `#`, `A`, `*`, `H` and `?` are placeholders (see [`MaskSlot`]), and placeholders
+    /// in square brackets are optional. Typing the literal after optional placeholders
+    /// skips the rest of them. A backslash escapes the next character, and anything else
+    /// is a literal
+    pub fn new(pattern: &str) -> Self {
+        let mut slots = Vec::new();
+        let mut optional = Vec::new();
+        let mut in_brackets = false;
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let slot = match c {
+                '[' => {
+                    in_brackets = true;
+                    continue;
+                }
+                ']' => {
+                    in_brackets = false;
+                    continue;
+                }
+                '#' => MaskSlot::Digit,
+                'A' => MaskSlot::Letter,
+                '*' => MaskSlot::Alphanumeric,
+                'H' => MaskSlot::Hex,
+                '?' => MaskSlot::Any,
+                '\\' => MaskSlot::Literal(chars.next().unwrap_or('\\')),
+                _ => MaskSlot::Literal(c),
+            };
+            optional.push(in_brackets && !matches!(slot, MaskSlot::Literal(_)));
+            slots.push(slot);
+        }
+        Self { slots, optional }
+    }
+
+    pub fn slots(&self) -> &[MaskSlot] {
+        &self.slots
+    }
+
+    /// Whether the slot at an index may be left out
+    pub fn is_optional(&self, index: usize) -> bool {
+        self.optional.get(index).copied().unwrap_or(false)
+    }
+
+    /// Strips the literals from a formatted value, leaving the entered characters.
+    ///
+    /// A literal ending optional placeholders early is kept, as it was typed
+    pub fn unformat(&self, value: &str) -> String {
+        let mut i = 0;
+        let mut raw = String::new();
+        for c in value.chars() {
+            while i < self.slots.len() {
+                match self.slots[i] {
+                    MaskSlot::Literal(l) if l == c => {
+                        i += 1;
+                        break;
+                    }
+                    MaskSlot::Literal(_) => i += 1,
+                    _ if self.optional[i] && self.terminator(i) == Some(c) => {
+                        raw.push(c);
+                        i = self.run_end(i) + 1;
+                        break;
+                    }
+                    _ => {
+                        raw.push(c);
+                        i += 1;
+                        break;
+                    }
+                }
+            }
+        }
+        raw
+    }
+
+    /// Lays out the entered characters, inserting literals up to the next placeholder.
+    ///
+    /// Returns `None` if a character is rejected by its slot or doesn't fit
+    pub fn format(&self, raw: &str) -> Option<String> {
+        self.layout(raw).map(|(formatted, _)| formatted)
+    }
+
+    /// Whether every required placeholder in the mask has been filled
+    pub fn is_complete(&self, value: &str) -> bool {
+        match self.layout(&self.unformat(value)) {
+            Some((_, reached)) => (reached..self.slots.len())
+                .all(|i| self.optional[i] || matches!(self.slots[i], MaskSlot::Literal(_))),
+            None => false,
+        }
+    }
+
+    /// Formats the entered characters, also returning the index of the first slot left
+    fn layout(&self, raw: &str) -> Option<(String, usize)> {
+        let mut formatted = String::new();
+        let mut chars = raw.chars().peekable();
+        if chars.peek().is_none() {
+            return Some((formatted, 0));
+        }
+        let mut i = 0;
+        while i < self.slots.len() {
+            let slot = self.slots[i];
+            match slot {
+                MaskSlot::Literal(l) => formatted.push(l),
+                _ => match chars.peek().copied() {
+                    Some(c) if self.optional[i] && self.terminator(i) == Some(c) => {
+                        // the literal is pushed as the next slot
+                        chars.next();
+                        i = self.run_end(i);
+                        continue;
+                    }
+                    Some(c) if slot.accepts(c) => {
+                        chars.next();
+                        formatted.push(c);
+                    }
+                    Some(_) => return None,
+                    None => break,
+                },
+            }
+            i += 1;
+        }
+        if chars.next().is_some() {
+            None
+        } else {
+            Some((formatted, i))
+        }
+    }
+
+    /// The index of the first slot after the optional placeholders starting at `index`
+    fn run_end(&self, index: usize) -> usize {
+        (index..self.slots.len())
+            .find(|i| !self.optional[*i])
+            .unwrap_or(self.slots.len())
+    }
+
+    /// The literal that ends the optional placeholders starting at `index` early
+    fn terminator(&self, index: usize) -> Option<char> {
+        match self.slots.get(self.run_end(index)) {
+            Some(MaskSlot::Literal(l)) => Some(*l),
+            _ => None,
+        }
+    }
+}
+
+pub type CharFilterFn = dyn Fn(char) -> bool + Send + Sync;
+
+/// Restricts which characters can be typed into an [`Input`](crate::components::Input)
+#[derive(Clone)]
+pub struct CharFilter(Arc<CharFilterFn>);
+
+impl CharFilter {
+    pub fn new<F: Fn(char) -> bool + Send + Sync + 'static>(f: F) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Only allows characters contained in `chars`
+    pub fn chars<S: Into<String>>(chars: S) -> Self {
+        let chars = chars.into();
+        Self::new(move |c| chars.contains(c))
+    }
+
+    pub fn allows(&self, c: char) -> bool {
+        (self.0)(c)
+    }
+}
+
+impl Debug for CharFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CharFilter(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_fixed_masks() {
+        let mask = InputMask::new("(###) ###-####");
+        assert_eq!(mask.format("555").as_deref(), Some("(555) "));
+        assert_eq!(mask.format("5551234567").as_deref(), Some("(555) 123-4567"));
+        assert_eq!(mask.format("55a"), None);
+        assert_eq!(mask.format("55512345678"), None);
+        assert_eq!(mask.unformat("(555) 123-4"), "5551234");
+        assert!(mask.is_complete("(555) 123-4567"));
+        assert!(!mask.is_complete("(555) 123-456"));
+    }
+
+    #[test]
+    fn escapes_placeholders() {
+        let mask = InputMask::new("\\##");
+        assert_eq!(mask.format("5").as_deref(), Some("#5"));
+    }
+
+    #[test]
+    fn ends_optional_segments_with_literals() {
+        let mask = InputMask::new("#[##].#[##].#[##].#[##]");
+        assert_eq!(mask.format("1.2").as_deref(), Some("1.2"));
+        assert_eq!(mask.format("192168").as_deref(), Some("192.168."));
+        assert_eq!(mask.format("1.20.3.4").as_deref(), Some("1.20.3.4"));
+        assert_eq!(mask.format(".1"), None);
+        assert_eq!(mask.unformat("1.2"), "1.2");
+        assert_eq!(mask.unformat("192.168.1"), "1921681");
+        assert_eq!(mask.unformat("10.0.0.1"), "10.0.0.1");
+        assert!(mask.is_complete("10.0.0.1"));
+        assert!(mask.is_complete("255.255.255.255"));
+        assert!(!mask.is_complete("10.0.0."));
+        assert!(!mask.is_complete("10.0"));
+    }
+
+    #[test]
+    fn round_trips_through_unformat() {
+        let mask = InputMask::new("#[##].#[##].#[##].#[##]");
+        for value in ["1.", "1.2", "12.3.", "123.4.56.7", "1.2.3.255"] {
+            assert_eq!(mask.format(&mask.unformat(value)).as_deref(), Some(value));
+        }
+    }
+}
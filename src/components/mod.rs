@@ -1,11 +1,25 @@
+mod breadcrumb;
 mod checkbox;
+mod completion;
 mod confirm;
 mod explorer;
+mod history;
 mod input;
+mod keymap;
+mod mask;
+mod text_area;
+mod validator;
 
 pub mod num_input;
 
+pub use breadcrumb::*;
 pub use checkbox::*;
+pub use completion::*;
 pub use confirm::*;
 pub use explorer::*;
+pub use history::*;
 pub use input::*;
+pub use keymap::{KeyBindings, VimMode};
+pub use mask::*;
+pub use text_area::*;
+pub use validator::*;
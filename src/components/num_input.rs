@@ -97,10 +97,11 @@ impl<T: InputSignedInt> Component for SignedIntInput<T> {
     fn handle_event(&mut self, event: crate::Event) -> Self::Response {
         if let Event::Key(key_event) = event {
             match key_event.code {
+                KeyCode::Char('-') => {
+                    self.invert();
+                }
                 KeyCode::Char(c) => {
-                    if !self.append_digit(c) && c == '-' {
-                        self.invert();
-                    }
+                    self.append_digit(c);
                 }
                 KeyCode::Backspace => {
                     self.remove_digit();
@@ -126,7 +127,7 @@ impl<T: InputSignedInt> Component for SignedIntInput<T> {
 }
 
 impl<T: InputSignedInt> Spannable for SignedIntInput<T> {
-    fn get_spans<'a, 'b>(&'a self) -> tui::text::Spans<'b> {
+    fn get_spans<'b>(&self) -> tui::text::Spans<'b> {
         let mut spans = Spans::default();
         spans.0.push(Span::styled(
             String::from(if self.negative { "- " } else { "+ " }),
@@ -245,7 +246,7 @@ impl<T: InputUnsignedInt> Component for UnsignedIntInput<T> {
 }
 
 impl<T: InputUnsignedInt> Spannable for UnsignedIntInput<T> {
-    fn get_spans<'a, 'b>(&'a self) -> Spans<'b> {
+    fn get_spans<'b>(&self) -> Spans<'b> {
         let mut spans = Spans::default();
         spans.0.push(Span::styled(
             String::from("> "),
@@ -315,7 +316,7 @@ impl<T: InputFloat> FloatInput<T> {
             let repr = initial_value.to_string();
             // TODO: make number parsing cleaner
             let has_decimal = repr.contains('.');
-            let is_negative = repr.chars().next().map_or(false, |c| c == '-');
+            let is_negative = repr.starts_with('-');
             let repr_no_sign = if is_negative { &repr[1..] } else { &repr[..] };
             if has_decimal {
                 let (first_maybe, second_maybe) = repr_no_sign.split_once('.').unwrap();
@@ -342,7 +343,7 @@ impl<T: InputFloat> FloatInput<T> {
         };
         Ok(FloatInput {
             value,
-            _phantom: PhantomData::default(),
+            _phantom: PhantomData,
         })
     }
 
@@ -465,7 +466,7 @@ impl<T: InputFloat> Component for FloatInput<T> {
 }
 
 impl<T: InputFloat> Spannable for FloatInput<T> {
-    fn get_spans<'a, 'b>(&'a self) -> Spans<'b> {
+    fn get_spans<'b>(&self) -> Spans<'b> {
         let mut spans = Spans::default();
         match &self.value {
             FloatValue::Infinity { negative } => {
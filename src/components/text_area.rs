@@ -0,0 +1,585 @@
+use crate::components::keymap::{byte_index, Edit, KeyAction, Keymap, Motion};
+use crate::components::KeyBindings;
+use crate::{Component, Event};
+use crossterm::event::{KeyCode, KeyModifiers};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Modifier, Style};
+use tui::text::Span;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How far PageUp and PageDown move the cursor
+const PAGE_LINES: usize = 10;
+
+/// A multi-line text editor
+#[derive(Debug, Clone)]
+pub struct TextArea {
+    lines: Vec<String>,
+    /// cursor line, and column in graphemes
+    cursor: (usize, usize),
+    /// first visible row, and first visible column when not wrapping
+    scroll: (usize, usize),
+    pub focused: bool,
+    wrap: bool,
+    line_numbers: bool,
+    submit_key: SubmitKey,
+    tab: TabMode,
+    text_style: Style,
+    cursor_style: Style,
+    line_number_style: Style,
+    keymap: Keymap,
+}
+
+/// Which key submits a [`TextArea`], with the other inserting a new line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitKey {
+    /// Enter submits, and Alt-Enter inserts a new line
+    Enter,
+    /// Ctrl-Enter submits, and Enter inserts a new line.
+    ///
+    /// Many terminals send Ctrl-Enter as Ctrl-J, so that submits as well
+    CtrlEnter,
+}
+
+/// What the Tab key does in a [`TextArea`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabMode {
+    /// Inserts spaces up to the next multiple of the width
+    Spaces(usize),
+    /// Leaves Tab unhandled, so the parent can use it to switch focus
+    Ignore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAreaResponse {
+    None,
+    Edited,
+    Submit,
+    Cancel,
+}
+
+/// A visual row: the line, and the range of graphemes shown from it
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextArea {
+    pub fn new() -> Self {
+        Self {
+            lines: vec![String::new()],
+            cursor: (0, 0),
+            scroll: (0, 0),
+            focused: false,
+            wrap: true,
+            line_numbers: false,
+            submit_key: SubmitKey::CtrlEnter,
+            tab: TabMode::Spaces(4),
+            text_style: Style::default(),
+            cursor_style: Style::default().add_modifier(Modifier::REVERSED),
+            line_number_style: Style::default().fg(Color::DarkGray),
+            keymap: Keymap::default(),
+        }
+    }
+
+    pub fn from_text(text: &str) -> Self {
+        let mut text_area = Self::new();
+        text_area.set_text(text);
+        text_area
+    }
+
+    /// Wraps long lines, rather than scrolling horizontally
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    pub fn submit_key(mut self, submit_key: SubmitKey) -> Self {
+        self.submit_key = submit_key;
+        self
+    }
+
+    pub fn tab(mut self, tab: TabMode) -> Self {
+        self.tab = tab;
+        self
+    }
+
+    pub fn text_style(mut self, style: Style) -> Self {
+        self.text_style = style;
+        self
+    }
+
+    pub fn cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    pub fn line_number_style(mut self, style: Style) -> Self {
+        self.line_number_style = style;
+        self
+    }
+
+    /// Sets the keybinding profile used for editing within a line
+    pub fn key_bindings(mut self, bindings: KeyBindings) -> Self {
+        self.keymap = Keymap::new(bindings);
+        self
+    }
+
+    /// The lines joined by newlines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Replaces the text, moving the cursor to the start
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text.lines().map(String::from).collect();
+        if self.lines.is_empty() || text.ends_with('\n') {
+            self.lines.push(String::new());
+        }
+        self.cursor = (0, 0);
+        self.scroll = (0, 0);
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// The cursor line, and column in graphemes
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Moves the cursor, clamping it to the text
+    pub fn set_cursor(&mut self, line: usize, column: usize) {
+        let line = line.min(self.lines.len() - 1);
+        let mut column = column;
+        self.keymap.clamp_cursor(&self.lines[line], &mut column);
+        self.cursor = (line, column);
+    }
+
+    fn insert_str(&mut self, text: &str) {
+        let (line, column) = self.cursor;
+        let index = byte_index(&self.lines[line], column);
+        self.lines[line].insert_str(index, text);
+        self.cursor.1 += text.graphemes(true).count();
+    }
+
+    fn insert_newline(&mut self) {
+        let (line, column) = self.cursor;
+        let index = byte_index(&self.lines[line], column);
+        let rest = self.lines[line].split_off(index);
+        self.lines.insert(line + 1, rest);
+        self.cursor = (line + 1, 0);
+    }
+
+    fn insert_tab(&mut self, width: usize) {
+        let width = width.max(1);
+        let spaces = width - self.cursor.1 % width;
+        self.insert_str(&" ".repeat(spaces));
+    }
+
+    /// Deletes the grapheme before the cursor, joining lines at the start of one
+    fn backspace(&mut self) -> bool {
+        let (line, column) = self.cursor;
+        if column > 0 {
+            let start = byte_index(&self.lines[line], column - 1);
+            let end = byte_index(&self.lines[line], column);
+            self.lines[line].replace_range(start..end, "");
+            self.cursor.1 -= 1;
+            true
+        } else if line > 0 {
+            let removed = self.lines.remove(line);
+            let previous = &mut self.lines[line - 1];
+            self.cursor = (line - 1, line_len(previous));
+            previous.push_str(&removed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Deletes the grapheme after the cursor, joining lines at the end of one
+    fn delete(&mut self) -> bool {
+        let (line, column) = self.cursor;
+        if column < line_len(&self.lines[line]) {
+            let start = byte_index(&self.lines[line], column);
+            let end = byte_index(&self.lines[line], column + 1);
+            self.lines[line].replace_range(start..end, "");
+            true
+        } else if line + 1 < self.lines.len() {
+            let removed = self.lines.remove(line + 1);
+            self.lines[line].push_str(&removed);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn move_left(&mut self) {
+        let (line, column) = self.cursor;
+        if column > 0 {
+            self.cursor.1 -= 1;
+        } else if line > 0 {
+            self.cursor = (line - 1, line_len(&self.lines[line - 1]));
+        }
+    }
+
+    fn move_right(&mut self) {
+        let (line, column) = self.cursor;
+        if column < line_len(&self.lines[line]) {
+            self.cursor.1 += 1;
+        } else if line + 1 < self.lines.len() {
+            self.cursor = (line + 1, 0);
+        }
+    }
+
+    fn move_vertical(&mut self, lines: isize) {
+        let line = (self.cursor.0 as isize + lines).max(0) as usize;
+        self.set_cursor(line, self.cursor.1);
+    }
+
+    /// Applies an edit from the keymap, returning whether the text changed.
+    ///
+    /// Deleting or moving past either end of a line carries on into the next one
+    fn apply_edit(&mut self, edit: Edit) -> bool {
+        let (line, column) = self.cursor;
+        let at_end = column >= line_len(&self.lines[line]);
+        let normal = self.keymap.is_normal_mode();
+        match edit {
+            Edit::Delete(Motion::Left) if column == 0 => self.backspace(),
+            Edit::Delete(Motion::Right) if at_end => self.delete(),
+            Edit::Move(Motion::Left) if column == 0 && !normal => {
+                self.move_left();
+                false
+            }
+            Edit::Move(Motion::Right) if at_end && !normal => {
+                self.move_right();
+                false
+            }
+            // like vim's `dd`, which removes the line rather than emptying it
+            Edit::KillLine if normal && self.lines.len() > 1 => {
+                self.keymap
+                    .apply(edit, &mut self.lines[line], &mut self.cursor.1);
+                self.lines.remove(line);
+                self.set_cursor(line, 0);
+                true
+            }
+            _ => self
+                .keymap
+                .apply(edit, &mut self.lines[line], &mut self.cursor.1),
+        }
+    }
+
+    fn is_submit(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match self.submit_key {
+            SubmitKey::Enter => code == KeyCode::Enter && modifiers.is_empty(),
+            SubmitKey::CtrlEnter => {
+                modifiers.contains(KeyModifiers::CONTROL)
+                    && matches!(code, KeyCode::Enter | KeyCode::Char('j'))
+            }
+        }
+    }
+
+    /// Splits the lines into visual rows for the given text width
+    fn rows(&self, width: usize) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            if !self.wrap || width == 0 {
+                rows.push(Row {
+                    line: index,
+                    start: 0,
+                    end: line_len(line),
+                });
+                continue;
+            }
+            let mut start = 0;
+            let mut row_width = 0;
+            for (i, grapheme) in line.graphemes(true).enumerate() {
+                let w = Span::raw(grapheme).width();
+                if row_width + w > width && i > start {
+                    rows.push(Row {
+                        line: index,
+                        start,
+                        end: i,
+                    });
+                    start = i;
+                    row_width = 0;
+                }
+                row_width += w;
+            }
+            rows.push(Row {
+                line: index,
+                start,
+                end: line_len(line),
+            });
+        }
+        rows
+    }
+
+    /// Scrolls so that the cursor is visible, returning its row
+    fn scroll_to_cursor(&mut self, rows: &[Row], width: usize, height: usize) -> usize {
+        let (line, column) = self.cursor;
+        let cursor_row = rows
+            .iter()
+            .rposition(|row| row.line == line && row.start <= column)
+            .unwrap_or(0);
+        if cursor_row < self.scroll.0 {
+            self.scroll.0 = cursor_row;
+        } else if height > 0 && cursor_row >= self.scroll.0 + height {
+            self.scroll.0 = cursor_row + 1 - height;
+        }
+        if self.wrap {
+            self.scroll.1 = 0;
+        } else {
+            let widths = self.lines[line]
+                .graphemes(true)
+                .take(column)
+                .map(|g| Span::raw(g).width())
+                .collect::<Vec<_>>();
+            let mut start = self.scroll.1.min(column);
+            // leave a cell for the cursor at the end of the line
+            while start < column && widths[start..].iter().sum::<usize>() + 1 > width {
+                start += 1;
+            }
+            self.scroll.1 = start;
+        }
+        cursor_row
+    }
+}
+
+impl Component for TextArea {
+    type Response = TextAreaResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key_event) = event {
+            let modifiers = key_event.modifiers;
+            if self.is_submit(key_event.code, modifiers) {
+                return TextAreaResponse::Submit;
+            }
+            let key_event = match self.keymap.translate(key_event) {
+                KeyAction::Edit(edit) => {
+                    return if self.apply_edit(edit) {
+                        TextAreaResponse::Edited
+                    } else {
+                        TextAreaResponse::None
+                    };
+                }
+                KeyAction::Forward(key) => key,
+                KeyAction::Consumed => return TextAreaResponse::None,
+            };
+            match key_event.code {
+                KeyCode::Enter if self.keymap.is_normal_mode() => {
+                    self.move_vertical(1);
+                    TextAreaResponse::None
+                }
+                KeyCode::Enter => {
+                    self.insert_newline();
+                    TextAreaResponse::Edited
+                }
+                KeyCode::Tab if self.keymap.is_normal_mode() => TextAreaResponse::None,
+                KeyCode::Tab => match self.tab {
+                    TabMode::Spaces(width) => {
+                        self.insert_tab(width);
+                        TextAreaResponse::Edited
+                    }
+                    TabMode::Ignore => TextAreaResponse::None,
+                },
+                KeyCode::Up => {
+                    self.move_vertical(-1);
+                    TextAreaResponse::None
+                }
+                KeyCode::Down => {
+                    self.move_vertical(1);
+                    TextAreaResponse::None
+                }
+                KeyCode::PageUp => {
+                    self.move_vertical(-(PAGE_LINES as isize));
+                    TextAreaResponse::None
+                }
+                KeyCode::PageDown => {
+                    self.move_vertical(PAGE_LINES as isize);
+                    TextAreaResponse::None
+                }
+                KeyCode::Esc => TextAreaResponse::Cancel,
+                _ => TextAreaResponse::None,
+            }
+        } else {
+            TextAreaResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
+        let gutter = if self.line_numbers {
+            self.lines.len().to_string().len() as u16 + 1
+        } else {
+            0
+        };
+        let width = rect.width.saturating_sub(gutter) as usize;
+        let height = rect.height as usize;
+        let rows = self.rows(width);
+        let cursor_row = self.scroll_to_cursor(&rows, width, height);
+
+        for (offset, row) in rows.iter().skip(self.scroll.0).take(height).enumerate() {
+            let y = rect.y + offset as u16;
+            let line = &self.lines[row.line];
+            if self.line_numbers && (row.start == 0 || offset == 0) {
+                let number = format!("{:>1$}", row.line + 1, gutter as usize - 1);
+                buf.set_string(rect.x, y, number, self.line_number_style);
+            }
+            let start = if self.wrap { row.start } else { self.scroll.1 };
+            let text = line
+                .graphemes(true)
+                .skip(start)
+                .take(row.end.saturating_sub(start))
+                .collect::<String>();
+            buf.set_stringn(rect.x + gutter, y, text, width, self.text_style);
+
+            if self.focused && self.scroll.0 + offset == cursor_row {
+                let before = line
+                    .graphemes(true)
+                    .skip(start)
+                    .take(self.cursor.1.saturating_sub(start))
+                    .map(|g| Span::raw(g).width())
+                    .sum::<usize>();
+                if before < width {
+                    let x = rect.x + gutter + before as u16;
+                    buf.get_mut(x, y).set_style(self.cursor_style);
+                }
+            }
+        }
+    }
+}
+
+fn line_len(line: &str) -> usize {
+    line.graphemes(true).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    fn spans(rows: &[Row]) -> Vec<(usize, usize, usize)> {
+        rows.iter()
+            .map(|row| (row.line, row.start, row.end))
+            .collect()
+    }
+
+    #[test]
+    fn wraps_rows_by_width() {
+        let text_area = TextArea::from_text("abcdefg\n\na中b");
+        assert_eq!(
+            spans(&text_area.rows(3)),
+            [
+                (0, 0, 3),
+                (0, 3, 6),
+                (0, 6, 7),
+                (1, 0, 0),
+                (2, 0, 2),
+                (2, 2, 3)
+            ]
+        );
+        // wide graphemes never share a row they don't fit in
+        assert_eq!(
+            spans(&text_area.rows(2))[5..],
+            [(2, 0, 1), (2, 1, 2), (2, 2, 3)]
+        );
+        let unwrapped = TextArea::from_text("abcdefg").wrap(false);
+        assert_eq!(spans(&unwrapped.rows(3)), [(0, 0, 7)]);
+    }
+
+    #[test]
+    fn scrolls_to_the_cursor() {
+        let mut text_area = TextArea::from_text("abcdefghij").wrap(false);
+        text_area.set_cursor(0, 10);
+        let rows = text_area.rows(4);
+        text_area.scroll_to_cursor(&rows, 4, 1);
+        // the last three graphemes, and a cell for the cursor
+        assert_eq!(text_area.scroll.1, 7);
+        text_area.set_cursor(0, 8);
+        text_area.scroll_to_cursor(&rows, 4, 1);
+        assert_eq!(text_area.scroll.1, 7);
+        text_area.set_cursor(0, 2);
+        text_area.scroll_to_cursor(&rows, 4, 1);
+        assert_eq!(text_area.scroll.1, 2);
+
+        let mut text_area = TextArea::from_text("0\n1\n2\n3\n4\n5");
+        text_area.set_cursor(5, 0);
+        let rows = text_area.rows(10);
+        assert_eq!(text_area.scroll_to_cursor(&rows, 10, 3), 5);
+        assert_eq!(text_area.scroll.0, 3);
+    }
+
+    #[test]
+    fn joins_lines_at_either_end() {
+        let mut text_area = TextArea::from_text("ab\ncd");
+        text_area.set_cursor(1, 0);
+        text_area.handle_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(text_area.text(), "abcd");
+        assert_eq!(text_area.cursor(), (0, 2));
+        text_area.handle_event(key(KeyCode::Enter, KeyModifiers::NONE));
+        text_area.set_cursor(0, 2);
+        text_area.handle_event(key(KeyCode::Delete, KeyModifiers::NONE));
+        assert_eq!(text_area.text(), "abcd");
+        assert_eq!(text_area.cursor(), (0, 2));
+    }
+
+    #[test]
+    fn submits_with_the_chosen_key() {
+        let mut text_area = TextArea::new().submit_key(SubmitKey::Enter);
+        assert_eq!(
+            text_area.handle_event(key(KeyCode::Enter, KeyModifiers::NONE)),
+            TextAreaResponse::Submit
+        );
+        text_area.handle_event(key(KeyCode::Enter, KeyModifiers::ALT));
+        assert_eq!(text_area.lines().len(), 2);
+
+        let mut text_area = TextArea::new().submit_key(SubmitKey::CtrlEnter);
+        text_area.handle_event(key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(text_area.lines().len(), 2);
+        for code in [KeyCode::Enter, KeyCode::Char('j')] {
+            assert_eq!(
+                text_area.handle_event(key(code, KeyModifiers::CONTROL)),
+                TextAreaResponse::Submit
+            );
+        }
+    }
+
+    #[test]
+    fn vim_normal_mode_removes_lines_and_ignores_tab() {
+        let mut text_area = TextArea::from_text("ab\ncd\nef").key_bindings(KeyBindings::Vim);
+        text_area.handle_event(key(KeyCode::Esc, KeyModifiers::NONE));
+        text_area.handle_event(key(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(text_area.text(), "ab\ncd\nef");
+        text_area.handle_event(key(KeyCode::Char('d'), KeyModifiers::NONE));
+        text_area.handle_event(key(KeyCode::Char('d'), KeyModifiers::NONE));
+        assert_eq!(text_area.text(), "cd\nef");
+        text_area.set_cursor(1, 1);
+        text_area.handle_event(key(KeyCode::Char('d'), KeyModifiers::NONE));
+        text_area.handle_event(key(KeyCode::Char('d'), KeyModifiers::NONE));
+        assert_eq!(text_area.text(), "cd");
+        assert_eq!(text_area.cursor(), (0, 0));
+        // `cc` empties the line to retype it
+        text_area.handle_event(key(KeyCode::Char('c'), KeyModifiers::NONE));
+        text_area.handle_event(key(KeyCode::Char('c'), KeyModifiers::NONE));
+        assert_eq!(text_area.lines(), [""]);
+    }
+}
@@ -0,0 +1,110 @@
+use regex::Regex;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub type ValidateFn = dyn Fn(&str) -> Result<(), String> + Send + Sync;
+
+/// A rule checked against the value of an [`Input`](crate::components::Input)
+#[derive(Clone)]
+pub enum Validator {
+    /// The entire value must match the regex, otherwise `message` is shown
+    Pattern { regex: Regex, message: String },
+    /// The number of graphemes in the value must lie within the bounds
+    Length {
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    /// A custom check, returning the error to display on failure
+    Custom(Arc<ValidateFn>),
+}
+
+impl Validator {
+    /// Creates a validator requiring the whole value to match `pattern`
+    pub fn pattern<M: Into<String>>(pattern: &str, message: M) -> Result<Self, regex::Error> {
+        Ok(Validator::Pattern {
+            regex: Regex::new(&format!("^(?:{})$", pattern))?,
+            message: message.into(),
+        })
+    }
+
+    pub fn length(min: Option<usize>, max: Option<usize>) -> Self {
+        Validator::Length { min, max }
+    }
+
+    pub fn custom<F: Fn(&str) -> Result<(), String> + Send + Sync + 'static>(f: F) -> Self {
+        Validator::Custom(Arc::new(f))
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            Validator::Pattern { regex, message } => {
+                if regex.is_match(value) {
+                    Ok(())
+                } else {
+                    Err(message.clone())
+                }
+            }
+            Validator::Length { min, max } => {
+                let len = value.graphemes(true).count();
+                match (min, max) {
+                    (Some(min), _) if len < *min => {
+                        Err(format!("must be at least {} characters", min))
+                    }
+                    (_, Some(max)) if len > *max => {
+                        Err(format!("must be at most {} characters", max))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            Validator::Custom(f) => f(value),
+        }
+    }
+}
+
+impl Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Validator::Pattern { regex, message } => f
+                .debug_struct("Pattern")
+                .field("regex", &regex.as_str())
+                .field("message", message)
+                .finish(),
+            Validator::Length { min, max } => f
+                .debug_struct("Length")
+                .field("min", min)
+                .field("max", max)
+                .finish(),
+            Validator::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_match_the_whole_value() {
+        let digits = Validator::pattern("[0-9]+", "digits only").unwrap();
+        assert!(digits.validate("123").is_ok());
+        assert_eq!(digits.validate("12a"), Err(String::from("digits only")));
+        assert!(digits.validate("a12").is_err());
+        // alternatives are grouped, so each must match the whole value
+        let either = Validator::pattern("ab|cd", "").unwrap();
+        assert!(either.validate("ab").is_ok());
+        assert!(either.validate("abx").is_err());
+        assert!(either.validate("xcd").is_err());
+    }
+
+    #[test]
+    fn length_counts_graphemes_within_bounds() {
+        let length = Validator::length(Some(2), Some(3));
+        assert!(length.validate("a").is_err());
+        assert!(length.validate("ab").is_ok());
+        assert!(length.validate("e\u{301}e\u{301}e\u{301}").is_ok());
+        assert!(length.validate("abcd").is_err());
+        assert!(Validator::length(None, Some(1)).validate("").is_ok());
+        assert!(Validator::length(Some(1), None).validate("abcdef").is_ok());
+    }
+}
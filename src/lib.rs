@@ -42,11 +42,17 @@ pub trait App {
     fn handle_event(&mut self, event: Event) -> AppResponse;
 
     fn draw(&mut self, rect: Rect, buffer: &mut Buffer);
+
+    /// Called between events, such as for collecting background work.
+    /// Returns whether the app should be redrawn
+    fn tick(&mut self) -> bool {
+        false
+    }
 }
 
 /// A trait for components that can be rendered as spans
 pub trait Spannable {
-    fn get_spans<'a, 'b>(&'a self) -> Spans<'b>;
+    fn get_spans<'b>(&self) -> Spans<'b>;
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -88,6 +94,10 @@ pub fn run<A: App>(app: &mut A, title: Option<String>) -> Result<(), ErrorKind>
                 AppResponse::None => {}
             }
         }
+
+        if app.tick() {
+            should_refresh = true;
+        }
     }
 
     close_terminal(&mut t)?;